@@ -0,0 +1,135 @@
+//! Compares the original substring-lookup classifier with the precompiled `WordMatcher`.
+//!
+//! Run with `cargo bench --bench classifier`. The legacy functions below reproduce
+//! `classify_word` as it was before the matcher: the corpus is rebuilt on every call and every
+//! substring of the word is looked up in the speller's `HashMap`.
+
+use std::collections::{HashMap, HashSet};
+use std::hint::black_box;
+use std::ops::Range;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use lorem_ipsumlike::dolor::corpus;
+use lorem_ipsumlike::english;
+use lorem_ipsumlike::ipsum::{scan_text, IpsumStatus};
+use lorem_ipsumlike::ipsum_classifier::IpsumClassifier;
+use lorem_ipsumlike::lorem::seeded_rng;
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use spellcheck::Speller;
+
+const TEXT_WORDS: usize = 20_000;
+
+/// Builds a deterministic text of English words, filler words and run-together identifiers.
+fn mixed_text() -> String {
+    let mut english: Vec<&str> = english::common_words().to_vec();
+    english.sort_unstable();
+    let mut filler: Vec<&str> = corpus().into_iter().collect();
+    filler.sort_unstable();
+    let mut rng = seeded_rng(0);
+    let mut words = Vec::with_capacity(TEXT_WORDS);
+    for _ in 0..TEXT_WORDS {
+        let word = match rng.random_range(0..3) {
+            0 => english.choose(&mut rng).unwrap().to_string(),
+            1 => filler.choose(&mut rng).unwrap().to_string(),
+            _ => (0..rng.random_range(2..5)).map(|_| *english.choose(&mut rng).unwrap()).collect(),
+        };
+        words.push(word);
+    }
+    words.join(" ")
+}
+
+/// `classify_word` before the matcher.
+fn legacy_classify_word(speller: &Speller, word: &str) -> IpsumStatus {
+    let corpus: HashSet<&'static str> = corpus();
+    let lower = word.to_lowercase();
+    if corpus.contains(lower.as_str()) {
+        return IpsumStatus::FullyIpsum;
+    }
+    if speller.n_words.contains_key(&lower) {
+        return IpsumStatus::NotLorem;
+    }
+    let mut best: Option<(String, Range<usize>)> = None;
+    for i in 0..lower.len() {
+        for j in (i + 1)..=lower.len() {
+            let Some(sub) = lower.get(i..j) else {
+                continue;
+            };
+            if speller.n_words.contains_key(sub) && sub.len() > best.as_ref().map_or(0, |b| b.0.len()) {
+                best = Some((sub.to_string(), i..j));
+            }
+        }
+    }
+    match best {
+        Some((valid, _)) if corpus.contains(valid.as_str()) => IpsumStatus::FullyIpsum,
+        Some((valid, range)) if valid.len() as f64 / lower.len() as f64 >= 0.7 => IpsumStatus::Partial { valid, range },
+        Some(_) => IpsumStatus::FullyIpsum,
+        None => IpsumStatus::NotLorem,
+    }
+}
+
+/// `scan_text` before the matcher, classifying each word with every-substring lookups.
+fn legacy_scan_text(words: &HashMap<String, u32>, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+    let mut results = Vec::new();
+    let mut index = 0;
+    for word in text.split_whitespace() {
+        let start = text[index..].find(word).map(|pos| index + pos).unwrap_or(index);
+        let end = start + word.len();
+        let lower = word.to_lowercase();
+        let status = if words.contains_key(&lower) {
+            IpsumStatus::NotLorem
+        } else {
+            let mut best: Option<Range<usize>> = None;
+            for i in 0..lower.len() {
+                for j in (i + 1)..=lower.len() {
+                    if lower.get(i..j).is_some_and(|sub| words.contains_key(sub))
+                        && j - i > best.as_ref().map_or(0, |b| b.len())
+                    {
+                        best = Some(i..j);
+                    }
+                }
+            }
+            match best {
+                Some(range) => IpsumStatus::Partial { valid: lower[range.clone()].to_string(), range },
+                None => IpsumStatus::FullyIpsum,
+            }
+        };
+        results.push((start..end, status));
+        index = end;
+    }
+    results
+}
+
+fn bench_classifier(c: &mut Criterion) {
+    let speller = english::speller();
+    let text = mixed_text();
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let classifier = IpsumClassifier::new(&speller);
+    assert!(words.iter().all(|w| classifier.classify(w) == legacy_classify_word(&speller, w)));
+
+    let mut group = c.benchmark_group("classify_20000_words");
+    group.sample_size(10);
+    group.bench_function("legacy", |b| {
+        b.iter(|| words.iter().filter(|w| legacy_classify_word(&speller, black_box(w)) == IpsumStatus::NotLorem).count())
+    });
+    group.bench_function("matcher", |b| {
+        b.iter(|| words.iter().filter(|w| classifier.classify(black_box(w)) == IpsumStatus::NotLorem).count())
+    });
+    group.bench_function("matcher_including_compile", |b| {
+        b.iter(|| {
+            let classifier = IpsumClassifier::new(&speller);
+            words.iter().filter(|w| classifier.classify(black_box(w)) == IpsumStatus::NotLorem).count()
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("scan_text_20000_words");
+    group.sample_size(10);
+    group.bench_function("legacy", |b| b.iter(|| legacy_scan_text(&speller.n_words, black_box(&text))));
+    group.bench_function("matcher", |b| b.iter(|| scan_text(&speller, black_box(&text))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_classifier);
+criterion_main!(benches);
@@ -0,0 +1,138 @@
+//! Compares the legacy `HashMap<String, Vec<String>>` chain with the interned, weighted
+//! `MarkovModel` on a large seed with a large vocabulary.
+//!
+//! Run with `cargo bench --bench markov_model`. Before the timings, the retained heap size of
+//! each representation is printed, measured with a counting global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use lorem_ipsumlike::dolor::corpus;
+use lorem_ipsumlike::lorem::seeded_rng;
+use lorem_ipsumlike::model::MarkovModel;
+use rand::prelude::{IndexedRandom, IteratorRandom};
+use rand::Rng;
+
+/// Tracks the number of bytes currently allocated through the system allocator.
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const SEED_WORDS: usize = 500_000;
+
+/// The number of distinct words in the seed besides the corpus itself.
+const VOCAB_WORDS: usize = 60_000;
+
+/// Builds a deterministic seed of `SEED_WORDS` words with occasional punctuation.
+///
+/// Corpus words alternate with words drawn from a vocabulary of `VOCAB_WORDS`, so each corpus
+/// word is followed by over a thousand distinct successors, as common words are in real text.
+fn large_seed() -> String {
+    let mut words: Vec<&str> = corpus().into_iter().collect();
+    words.sort();
+    let vocab: Vec<String> =
+        (0..VOCAB_WORDS).map(|i| format!("{}{}", words[i % words.len()], i / words.len())).collect();
+    let mut rng = seeded_rng(0);
+    let mut seed = String::new();
+    for i in 0..SEED_WORDS {
+        if i > 0 {
+            seed.push(' ');
+        }
+        if i % 2 == 0 {
+            seed.push_str(words.choose(&mut rng).unwrap());
+        } else {
+            seed.push_str(vocab.choose(&mut rng).unwrap());
+        }
+        if rng.random_ratio(1, 12) {
+            seed.push(if rng.random_ratio(1, 3) { '.' } else { ',' });
+        }
+    }
+    seed
+}
+
+/// The legacy chain representation, built the way `build_markov_chain` used to.
+fn legacy_chain(seed: &str) -> HashMap<String, Vec<String>> {
+    let mut chain: HashMap<String, Vec<String>> = HashMap::new();
+    let words: Vec<&str> = seed.split_whitespace().collect();
+    for window in words.windows(2) {
+        chain.entry(window[0].to_lowercase()).or_default().push(window[1].to_string());
+    }
+    chain
+}
+
+/// The legacy generator: a uniform choice over every stored successor occurrence.
+fn legacy_generate<R: Rng>(chain: &HashMap<String, Vec<String>>, num_words: usize, rng: &mut R) -> String {
+    let start = chain.keys().choose(rng).unwrap().to_string();
+    let mut result = vec![start.clone()];
+    let mut current = start;
+    for _ in 1..num_words {
+        match chain.get(&current.to_lowercase()) {
+            Some(choices) => {
+                current = choices.choose(rng).unwrap().clone();
+                result.push(current.clone());
+            }
+            None => break,
+        }
+    }
+    result.join(" ")
+}
+
+/// Returns the value built by `f` along with the number of heap bytes it retains.
+fn retained<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = f();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+    (value, after.saturating_sub(before))
+}
+
+fn bench_markov_model(c: &mut Criterion) {
+    let seed = large_seed();
+
+    let (chain, chain_bytes) = retained(|| legacy_chain(&seed));
+    let (model, model_bytes) = retained(|| MarkovModel::train(&seed, 1));
+    println!(
+        "retained heap for {} seed words: legacy chain {:.1} MiB, MarkovModel {:.1} MiB ({:.1}x smaller)",
+        SEED_WORDS,
+        chain_bytes as f64 / (1024.0 * 1024.0),
+        model_bytes as f64 / (1024.0 * 1024.0),
+        chain_bytes as f64 / model_bytes.max(1) as f64,
+    );
+
+    let mut group = c.benchmark_group("train");
+    group.sample_size(10);
+    group.bench_function("legacy_chain", |b| b.iter(|| legacy_chain(black_box(&seed))));
+    group.bench_function("markov_model", |b| b.iter(|| MarkovModel::train(black_box(&seed), 1)));
+    group.finish();
+
+    let mut group = c.benchmark_group("generate_1000_words");
+    group.bench_function("legacy_chain", |b| {
+        let mut rng = seeded_rng(1);
+        b.iter(|| legacy_generate(black_box(&chain), 1000, &mut rng))
+    });
+    group.bench_function("markov_model", |b| {
+        let mut rng = seeded_rng(1);
+        b.iter(|| model.generate_with_rng(1000, &mut rng).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_markov_model);
+criterion_main!(benches);
@@ -4,15 +4,12 @@ use std::fs;
 use std::io::{self, BufRead};
 use std::path::Path;
 
-use lorem_ipsumlike::ipsum::classify_word_with_speller;
-use lorem_ipsumlike::ipsum_classifier;
 // Import the external spellcheck crate.
 use spellcheck::Speller;
 
-// Import our corpus (from the dolar module) and classifier.
-use lorem_ipsumlike::dolor::corpus;
+// Import our classifier.
 use lorem_ipsumlike::ipsum::IpsumStatus;
-use lorem_ipsumlike::ipsum_classifier::{classify_word};
+use lorem_ipsumlike::ipsum_classifier::classify_word;
 
 fn main() -> anyhow::Result<()> {
     // Load training data from training.txt in the manifest directory.
@@ -37,7 +34,7 @@ fn main() -> anyhow::Result<()> {
     // Here, we assume that both FullyIpsum and Partial are not real words.
     let filtered_words: Vec<&str> = input_text
         .split_whitespace()
-        .filter(|word| lorem_ipsumlike::ipsum_classifier::classify_word(&speller, word) == IpsumStatus::NotLorem)
+        .filter(|word| classify_word(&speller, word) == IpsumStatus::NotLorem)
         .collect();
 
     // Print the filtered text.
@@ -1,6 +1,9 @@
 use std::env;
 use std::io::{self, BufRead};
 
+use lorem_ipsumlike::model::MarkovModel;
+use lorem_ipsumlike::watermark::Watermark;
+
 /// Inserts a filler word between each pair of words in the input sentence.
 /// For example, "Hello world" with filler "lorem" becomes "Hello lorem world".
 fn insert_ipsum_between(sentence: &str, filler: &str) -> String {
@@ -37,7 +40,7 @@ fn main() {
     } else {
         // Read all lines from stdin.
         let stdin = io::stdin();
-        stdin.lock().lines().filter_map(Result::ok).collect::<Vec<String>>().join(" ")
+        stdin.lock().lines().map_while(Result::ok).collect::<Vec<String>>().join(" ")
     };
 
     if real_sentence.trim().is_empty() {
@@ -54,5 +57,26 @@ fn main() {
 
     // Remove the filler words to get back the original sentence.
     let cleaned = remove_filler(&generated, filler);
-    println!("Cleaned text:\n{}", cleaned);
+    println!("Cleaned text:\n{}\n", cleaned);
+
+    // Anyone can strip a fixed filler word. A keyed watermark picks its words and positions
+    // with a secret, so only the key holder can remove them, and removal verifies the text.
+    let key = env::var("LOREM_KEY").unwrap_or_else(|_| "example key".to_string());
+    let model = MarkovModel::train(
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.",
+        1,
+    );
+    let watermark = Watermark::new(key.as_bytes(), &model)
+        .expect("the seed has words")
+        .with_density(0.5);
+    match watermark.embed(&real_sentence) {
+        Ok(tagged) => {
+            println!("Watermarked text:\n{}\n", tagged);
+            match watermark.extract(&tagged) {
+                Ok(clean) => println!("Verified and extracted:\n{}", clean),
+                Err(err) => println!("Extraction failed: {}", err),
+            }
+        }
+        Err(err) => println!("Could not watermark: {}", err),
+    }
 }
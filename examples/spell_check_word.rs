@@ -2,7 +2,6 @@
 use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::io::Write;
 use std::path::Path;
 use spellcheck::Speller;
 
@@ -10,8 +9,8 @@ fn main() {
     // Expect exactly one argument: the word to check.
     let args: Vec<String> = env::args().collect();
     if args.len() != 2 {
-        writeln!(std::io::stderr(), "Usage: {} <word>", args[0]).unwrap();
-        writeln!(std::io::stderr(), "Example: {} tometo", args[0]).unwrap();
+        eprintln!("Usage: {} <word>", args[0]);
+        eprintln!("Example: {} tometo", args[0]);
         std::process::exit(1);
     }
 
@@ -0,0 +1,671 @@
+//! A common interface for the ways this crate tells filler from real words.
+//!
+//! A [`Classifier`] decides the [`IpsumStatus`] of a word, and of every word of a text. The
+//! strategies of the crate all implement it:
+//!
+//! - a [`Speller`], a [`WordMatcher`] compiled from one, or a [`DictionaryClassifier`]: a word
+//!   the dictionary knows is real, anything else is filler, as in
+//!   [`classify_word_with_speller`](crate::ipsum::classify_word_with_speller);
+//! - an [`IpsumClassifier`]: corpus words are filler first, then the dictionary and the 70%
+//!   substring rule decide, as in [`classify_word`](crate::ipsum_classifier::classify_word);
+//! - a [`CorpusClassifier`]: the corpus alone, with no dictionary;
+//! - a [`ContextClassifier`](crate::context::ContextClassifier), which works on whole texts.
+//!
+//! Each can also say why it classified a word as it did, with [`Classifier::explain_word`] and
+//! [`Classifier::explain_text`]. The classifiers that are structs take a [`ClassifierConfig`]
+//! with `with_config`.
+//! [`Any`], [`All`] and [`WeightedVote`] combine several classifiers into one, and
+//! [`scan_text`](crate::ipsum::scan_text) and [`remove_ipsum`](crate::ipsum::remove_ipsum) take
+//! any of them.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use spellcheck::Speller;
+
+use crate::config::ClassifierConfig;
+use crate::dolor;
+use crate::ipsum::{
+    classify_word_with_matcher, classify_word_with_matcher_and_config, classify_word_with_speller,
+    explain_word_with_matcher_and_config, explain_word_with_speller, IpsumStatus, LoweredWord,
+};
+use crate::ipsum_classifier::{Explanation, IpsumClassifier, Rule};
+use crate::matcher::WordMatcher;
+use crate::tokenizer::{self, TokenKind};
+
+/// Decides whether words are filler.
+pub trait Classifier {
+    /// Classifies a single word.
+    fn classify_word(&self, word: &str) -> IpsumStatus;
+
+    /// Classifies every word of `text`, as split by [`tokenizer::words`], returning each
+    /// word's byte range and status. Punctuation and numbers are skipped.
+    ///
+    /// The default classifies the words one at a time. Classifiers that weigh words against
+    /// their neighbours, or that prepare something once per text, override it.
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        tokenizer::words(text)
+            .filter(|word| word.kind == TokenKind::Word)
+            .map(|word| (word.bytes, self.classify_word(word.text)))
+            .collect()
+    }
+
+    /// [`classify_word`](Classifier::classify_word), along with the rule that decided the
+    /// word. The default gives the status alone, under [`Rule::Unexplained`].
+    fn explain_word(&self, word: &str) -> Explanation {
+        Explanation::unexplained(self.classify_word(word))
+    }
+
+    /// [`classify_text`](Classifier::classify_text), with each word's status explained.
+    ///
+    /// The default explains each word with [`explain_word`](Classifier::explain_word), and
+    /// falls back to [`Rule::Unexplained`] where that disagrees with `classify_text`.
+    /// Classifiers that override `classify_text` should override this too.
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        self.classify_text(text)
+            .into_iter()
+            .map(|(range, status)| {
+                let explanation = self.explain_word(&text[range.clone()]);
+                (range, if explanation.status == status { explanation } else { Explanation::unexplained(status) })
+            })
+            .collect()
+    }
+}
+
+impl<C: Classifier + ?Sized> Classifier for &C {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        (**self).classify_word(word)
+    }
+
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        (**self).classify_text(text)
+    }
+
+    fn explain_word(&self, word: &str) -> Explanation {
+        (**self).explain_word(word)
+    }
+
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        (**self).explain_text(text)
+    }
+}
+
+impl<C: Classifier + ?Sized> Classifier for Box<C> {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        (**self).classify_word(word)
+    }
+
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        (**self).classify_text(text)
+    }
+
+    fn explain_word(&self, word: &str) -> Explanation {
+        (**self).explain_word(word)
+    }
+
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        (**self).explain_text(text)
+    }
+}
+
+impl Classifier for Speller {
+    /// [`classify_word_with_speller`].
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        classify_word_with_speller(self, word)
+    }
+
+    /// Compiles the dictionary into a [`WordMatcher`] once for the whole text. To classify
+    /// many texts, compile the matcher once with [`WordMatcher::from_speller`] and use that.
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        WordMatcher::from_speller(self).classify_text(text)
+    }
+
+    /// The dictionary rules, with the word's frequency in the dictionary.
+    fn explain_word(&self, word: &str) -> Explanation {
+        explain_word_with_speller(self, word).with_frequency(self, word)
+    }
+
+    /// Compiles the dictionary once for the whole text, like
+    /// [`classify_text`](Classifier::classify_text), then adds the frequencies.
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        WordMatcher::from_speller(self)
+            .explain_text(text)
+            .into_iter()
+            .map(|(range, explanation)| {
+                let word = &text[range.clone()];
+                (range, explanation.with_frequency(self, word))
+            })
+            .collect()
+    }
+}
+
+impl Classifier for WordMatcher {
+    /// [`classify_word_with_matcher`].
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        classify_word_with_matcher(self, word)
+    }
+
+    fn explain_word(&self, word: &str) -> Explanation {
+        explain_word_with_matcher_and_config(self, word, &ClassifierConfig::default())
+    }
+}
+
+/// The dictionary rules of a [`WordMatcher`], with a [`ClassifierConfig`].
+#[derive(Debug, Clone)]
+pub struct DictionaryClassifier {
+    dictionary: WordMatcher,
+    config: ClassifierConfig,
+}
+
+impl DictionaryClassifier {
+    /// Compiles the dictionary of `speller`, with the default configuration.
+    pub fn new(speller: &Speller) -> Self {
+        DictionaryClassifier { dictionary: WordMatcher::from_speller(speller), config: ClassifierConfig::default() }
+    }
+
+    /// Sets the minimum lengths, word lists and case handling.
+    pub fn with_config(mut self, config: ClassifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl Classifier for DictionaryClassifier {
+    /// [`classify_word_with_matcher_and_config`].
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        classify_word_with_matcher_and_config(&self.dictionary, word, &self.config)
+    }
+
+    fn explain_word(&self, word: &str) -> Explanation {
+        explain_word_with_matcher_and_config(&self.dictionary, word, &self.config)
+    }
+}
+
+impl Classifier for IpsumClassifier {
+    /// [`IpsumClassifier::classify`].
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        self.classify(word)
+    }
+
+    /// [`IpsumClassifier::explain`].
+    fn explain_word(&self, word: &str) -> Explanation {
+        self.explain(word)
+    }
+}
+
+/// Classifies words by the [`dolor::corpus`] alone, for text without a dictionary to check
+/// against.
+///
+/// A corpus word is filler, and so is a word that is mostly a corpus word: one where the
+/// longest corpus word inside it covers at least
+/// [`min_partial_ratio`](ClassifierConfig::min_partial_ratio) (70% by default) of its
+/// characters, such as "loremx". Everything else is real. Short corpus words such as "a" and
+/// "in" turn up inside most English words, so shorter matches say nothing.
+#[derive(Debug, Clone, Default)]
+pub struct CorpusClassifier {
+    config: ClassifierConfig,
+}
+
+impl CorpusClassifier {
+    /// A classifier with the default configuration.
+    pub fn new() -> Self {
+        CorpusClassifier::default()
+    }
+
+    /// Sets the thresholds, word lists and case handling.
+    pub fn with_config(mut self, config: ClassifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl Classifier for CorpusClassifier {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        self.explain_word(word).status
+    }
+
+    fn explain_word(&self, word: &str) -> Explanation {
+        let explain = |status, rule| Explanation::new(status, rule, &self.config);
+        if self.config.is_ignored(word) {
+            return explain(IpsumStatus::NotLorem, Rule::Ignored);
+        }
+        if self.config.skips(word) {
+            return explain(IpsumStatus::NotLorem, Rule::TooShort);
+        }
+        let lower = LoweredWord::with_case(word, self.config.case_sensitive);
+        let corpus = dolor::corpus_matcher();
+        if corpus.contains(lower.as_str()) {
+            return explain(IpsumStatus::FullyIpsum, Rule::CorpusWord);
+        }
+        match corpus.longest_match(lower.as_str()).filter(|range| self.config.is_long_enough(&lower.as_str()[range.clone()])) {
+            Some(range) if lower.coverage(range.clone()) >= self.config.min_partial_ratio => {
+                explain(IpsumStatus::FullyIpsum, Rule::MostlyCorpusWord).with_substring(&lower, range)
+            }
+            Some(range) => explain(IpsumStatus::NotLorem, Rule::NotCorpusWord).with_substring(&lower, range),
+            None => explain(IpsumStatus::NotLorem, Rule::NotCorpusWord),
+        }
+    }
+}
+
+/// A word's status from one member of a combination, alone or with its explanation, so the
+/// combinations pick statuses and explanations the same way.
+trait Verdict: Clone {
+    fn status(&self) -> &IpsumStatus;
+    /// The verdict when no member decides: the word is real.
+    fn real() -> Self;
+    fn of_word(classifier: &dyn Classifier, word: &str) -> Self;
+    fn of_text(classifier: &dyn Classifier, text: &str) -> Vec<(Range<usize>, Self)>;
+}
+
+impl Verdict for IpsumStatus {
+    fn status(&self) -> &IpsumStatus {
+        self
+    }
+
+    fn real() -> Self {
+        IpsumStatus::NotLorem
+    }
+
+    fn of_word(classifier: &dyn Classifier, word: &str) -> Self {
+        classifier.classify_word(word)
+    }
+
+    fn of_text(classifier: &dyn Classifier, text: &str) -> Vec<(Range<usize>, Self)> {
+        classifier.classify_text(text)
+    }
+}
+
+impl Verdict for Explanation {
+    fn status(&self) -> &IpsumStatus {
+        &self.status
+    }
+
+    fn real() -> Self {
+        Explanation::unexplained(IpsumStatus::NotLorem)
+    }
+
+    fn of_word(classifier: &dyn Classifier, word: &str) -> Self {
+        classifier.explain_word(word)
+    }
+
+    fn of_text(classifier: &dyn Classifier, text: &str) -> Vec<(Range<usize>, Self)> {
+        classifier.explain_text(text)
+    }
+}
+
+/// The verdict of the member `decide` picks from the members' statuses, or a real word if it
+/// picks none.
+fn pick<V: Verdict>(mut verdicts: Vec<V>, decide: impl Fn(&[&IpsumStatus]) -> Option<usize>) -> V {
+    let statuses: Vec<&IpsumStatus> = verdicts.iter().map(V::status).collect();
+    match decide(&statuses) {
+        Some(i) => verdicts.swap_remove(i),
+        None => V::real(),
+    }
+}
+
+/// Classifies `word` with every member and picks the deciding member's verdict.
+fn combine_word<V: Verdict>(
+    members: &[&dyn Classifier],
+    word: &str,
+    decide: impl Fn(&[&IpsumStatus]) -> Option<usize>,
+) -> V {
+    pick(members.iter().map(|member| V::of_word(*member, word)).collect(), decide)
+}
+
+/// Classifies `text` with every member and picks the deciding member's verdict word by word,
+/// over the words of [`tokenizer::words`]. Members' verdicts are matched to those words by
+/// byte range; a word a member split differently, or left out, is classified with its
+/// [`Classifier::classify_word`].
+fn combine_text<V: Verdict>(
+    members: &[&dyn Classifier],
+    text: &str,
+    decide: impl Fn(&[&IpsumStatus]) -> Option<usize>,
+) -> Vec<(Range<usize>, V)> {
+    let verdicts: Vec<HashMap<Range<usize>, V>> =
+        members.iter().map(|member| V::of_text(*member, text).into_iter().collect()).collect();
+    tokenizer::words(text)
+        .filter(|word| word.kind == TokenKind::Word)
+        .map(|word| {
+            let word_verdicts: Vec<V> = members
+                .iter()
+                .zip(&verdicts)
+                .map(|(member, verdicts)| verdicts.get(&word.bytes).cloned().unwrap_or_else(|| V::of_word(*member, word.text)))
+                .collect();
+            (word.bytes, pick(word_verdicts, &decide))
+        })
+        .collect()
+}
+
+/// Filler if any member says so, with the status of the first member that does.
+#[derive(Default)]
+pub struct Any<'a> {
+    members: Vec<Box<dyn Classifier + 'a>>,
+}
+
+impl<'a> Any<'a> {
+    /// A combination without members, under which nothing is filler.
+    pub fn new() -> Self {
+        Any { members: Vec::new() }
+    }
+
+    /// Adds a member.
+    pub fn with(mut self, classifier: impl Classifier + 'a) -> Self {
+        self.members.push(Box::new(classifier));
+        self
+    }
+
+    fn members(&self) -> Vec<&dyn Classifier> {
+        self.members.iter().map(|m| m.as_ref() as &dyn Classifier).collect()
+    }
+
+    /// The first member that says filler, or else the first member.
+    fn decide(statuses: &[&IpsumStatus]) -> Option<usize> {
+        statuses.iter().position(|status| status.is_filler()).or((!statuses.is_empty()).then_some(0))
+    }
+}
+
+impl Classifier for Any<'_> {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        combine_word(&self.members(), word, Any::decide)
+    }
+
+    /// Combines the members' [`Classifier::classify_text`] word by word, so members that
+    /// weigh context keep doing so. A word a member splits differently is classified with that
+    /// member's [`Classifier::classify_word`].
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        combine_text(&self.members(), text, Any::decide)
+    }
+
+    /// The explanation of the member whose status was taken.
+    fn explain_word(&self, word: &str) -> Explanation {
+        combine_word(&self.members(), word, Any::decide)
+    }
+
+    /// The explanations of the members whose statuses were taken.
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        combine_text(&self.members(), text, Any::decide)
+    }
+}
+
+/// Filler only if every member says so, with the status of the first member; a
+/// combination without members calls nothing filler.
+#[derive(Default)]
+pub struct All<'a> {
+    members: Vec<Box<dyn Classifier + 'a>>,
+}
+
+impl<'a> All<'a> {
+    /// A combination without members, under which nothing is filler.
+    pub fn new() -> Self {
+        All { members: Vec::new() }
+    }
+
+    /// Adds a member.
+    pub fn with(mut self, classifier: impl Classifier + 'a) -> Self {
+        self.members.push(Box::new(classifier));
+        self
+    }
+
+    fn members(&self) -> Vec<&dyn Classifier> {
+        self.members.iter().map(|m| m.as_ref() as &dyn Classifier).collect()
+    }
+
+    /// The first member that says real, or else the first member.
+    fn decide(statuses: &[&IpsumStatus]) -> Option<usize> {
+        statuses.iter().position(|status| !status.is_filler()).or((!statuses.is_empty()).then_some(0))
+    }
+}
+
+impl Classifier for All<'_> {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        combine_word(&self.members(), word, All::decide)
+    }
+
+    /// Combines the members' [`Classifier::classify_text`] word by word, so members that
+    /// weigh context keep doing so. A word a member splits differently is classified with that
+    /// member's [`Classifier::classify_word`].
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        combine_text(&self.members(), text, All::decide)
+    }
+
+    /// The explanation of the first member that calls the word real, or of the first member
+    /// if they all call it filler.
+    fn explain_word(&self, word: &str) -> Explanation {
+        combine_word(&self.members(), word, All::decide)
+    }
+
+    /// [`All::explain_word`] for every word, in context.
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        combine_text(&self.members(), text, All::decide)
+    }
+}
+
+/// Filler if the members that say so carry more than half of the total weight. The status
+/// is that of the heaviest of them, the first on ties.
+#[derive(Default)]
+pub struct WeightedVote<'a> {
+    members: Vec<(Box<dyn Classifier + 'a>, f64)>,
+}
+
+impl<'a> WeightedVote<'a> {
+    /// A vote without members, under which nothing is filler.
+    pub fn new() -> Self {
+        WeightedVote { members: Vec::new() }
+    }
+
+    /// Adds a member whose vote counts `weight` times. Negative weights count as 0.
+    pub fn with(mut self, classifier: impl Classifier + 'a, weight: f64) -> Self {
+        self.members.push((Box::new(classifier), weight.max(0.0)));
+        self
+    }
+
+    fn members(&self) -> Vec<&dyn Classifier> {
+        self.members.iter().map(|(m, _)| m.as_ref() as &dyn Classifier).collect()
+    }
+
+    /// The heaviest member on the winning side, the first on ties.
+    fn decide(&self, statuses: &[&IpsumStatus]) -> Option<usize> {
+        let total: f64 = self.members.iter().map(|(_, weight)| weight).sum();
+        let flagged: f64 =
+            statuses.iter().zip(&self.members).filter(|(status, _)| status.is_filler()).map(|(_, (_, weight))| weight).sum();
+        let filler = flagged * 2.0 > total;
+        let mut heaviest: Option<(usize, f64)> = None;
+        for (i, (status, &(_, weight))) in statuses.iter().zip(&self.members).enumerate() {
+            if status.is_filler() == filler && heaviest.is_none_or(|(_, heaviest)| weight > heaviest) {
+                heaviest = Some((i, weight));
+            }
+        }
+        heaviest.map(|(i, _)| i)
+    }
+}
+
+impl Classifier for WeightedVote<'_> {
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        combine_word(&self.members(), word, |statuses| self.decide(statuses))
+    }
+
+    /// Combines the members' [`Classifier::classify_text`] word by word, so members that
+    /// weigh context keep doing so. A word a member splits differently is classified with that
+    /// member's [`Classifier::classify_word`].
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        combine_text(&self.members(), text, |statuses| self.decide(statuses))
+    }
+
+    /// The explanation of the heaviest member on the winning side.
+    fn explain_word(&self, word: &str) -> Explanation {
+        combine_word(&self.members(), word, |statuses| self.decide(statuses))
+    }
+
+    /// [`WeightedVote::explain_word`] for every word, in context.
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        combine_text(&self.members(), text, |statuses| self.decide(statuses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::ContextClassifier;
+    use crate::english;
+    use crate::ipsum::{remove_ipsum, scan_text};
+    use crate::ipsum_classifier::classify_word;
+
+    fn train_speller() -> Speller {
+        let mut speller = Speller { letters: "abcdefghijklmnopqrstuvwxyz".to_string(), n_words: HashMap::new() };
+        speller.train("tomato potato lorem");
+        speller
+    }
+
+    #[test]
+    fn test_strategies_match_their_functions() {
+        let speller = train_speller();
+        let matcher = WordMatcher::from_speller(&speller);
+        let rules = IpsumClassifier::new(&speller);
+        for word in ["lorem", "Ipsum", "tomato", "tomatox", "xyzzy", "loremps", ""] {
+            assert_eq!(speller.classify_word(word), classify_word_with_speller(&speller, word), "{}", word);
+            assert_eq!(matcher.classify_word(word), classify_word_with_speller(&speller, word), "{}", word);
+            assert_eq!(rules.classify_word(word), classify_word(&speller, word), "{}", word);
+        }
+        let text = "Tomato, lorem_ipsum and xyzzy.";
+        assert_eq!(speller.classify_text(text), matcher.classify_text(text));
+
+        let corpus = CorpusClassifier::new();
+        assert_eq!(corpus.classify_word("Lorem"), IpsumStatus::FullyIpsum);
+        assert_eq!(corpus.classify_word("loremx"), IpsumStatus::FullyIpsum);
+        // "banana" contains the corpus word "a", but only a sixth of it.
+        assert_eq!(corpus.classify_word("banana"), IpsumStatus::NotLorem);
+        assert_eq!(corpus.classify_word("tomato"), IpsumStatus::NotLorem);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let speller = train_speller();
+        // The dictionary knows "lorem" but not "xyzzy"; the corpus rules say the opposite.
+        let rules = IpsumClassifier::new(&speller);
+        let any = Any::new().with(&speller).with(&rules);
+        let all = All::new().with(&speller).with(&rules);
+        for word in ["lorem", "xyzzy"] {
+            assert!(any.classify_word(word).is_filler(), "{}", word);
+            assert_eq!(all.classify_word(word), IpsumStatus::NotLorem, "{}", word);
+        }
+        assert_eq!(any.classify_word("tomato"), IpsumStatus::NotLorem);
+        assert_eq!(all.classify_word("ipsum"), IpsumStatus::FullyIpsum);
+
+        let vote = WeightedVote::new().with(&speller, 1.0).with(&rules, 1.0).with(CorpusClassifier::new(), 1.0);
+        assert_eq!(vote.classify_word("lorem"), IpsumStatus::FullyIpsum);
+        assert_eq!(vote.classify_word("xyzzy"), IpsumStatus::NotLorem);
+        let trusting = WeightedVote::new().with(&speller, 3.0).with(&rules, 1.0).with(CorpusClassifier::new(), 1.0);
+        assert_eq!(trusting.classify_word("xyzzy"), IpsumStatus::FullyIpsum);
+        assert_eq!(trusting.classify_word("lorem"), IpsumStatus::NotLorem);
+        // The heaviest voter decides the status.
+        assert_eq!(trusting.classify_word("tomatox"), IpsumStatus::Partial { valid: "tomato".to_string(), range: 0..6 });
+
+        for empty in [&Any::new() as &dyn Classifier, &All::new(), &WeightedVote::new()] {
+            assert_eq!(empty.classify_word("lorem"), IpsumStatus::NotLorem);
+            assert_eq!(empty.classify_text("lorem ipsum").len(), 2);
+        }
+    }
+
+    /// Splits text at whitespace only, calling every chunk filler.
+    struct Chunks;
+
+    impl Classifier for Chunks {
+        fn classify_word(&self, _word: &str) -> IpsumStatus {
+            IpsumStatus::NotLorem
+        }
+
+        fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+            text.split_whitespace()
+                .map(|chunk| {
+                    let start = chunk.as_ptr() as usize - text.as_ptr() as usize;
+                    (start..start + chunk.len(), IpsumStatus::FullyIpsum)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_combinators_align_words_by_range() {
+        let speller = train_speller();
+        let text = "tomato lorem_ipsum, potato and xyzzy";
+        // Chunks agrees with the tokenizer on "tomato", "potato", "and" and "xyzzy" but not on
+        // "lorem_ipsum,", so its word-level verdict is used for "lorem" and "ipsum".
+        let any = Any::new().with(&speller).with(Chunks);
+        let words: Vec<(&str, bool)> =
+            any.classify_text(text).into_iter().map(|(range, status)| (&text[range], status.is_filler())).collect();
+        assert_eq!(words, [("tomato", true), ("lorem", false), ("ipsum", true), ("potato", true), ("and", true), ("xyzzy", true)]);
+        let all = All::new().with(Chunks).with(&speller);
+        let flagged: Vec<&str> =
+            all.classify_text(text).into_iter().filter(|(_, s)| s.is_filler()).map(|(range, _)| &text[range]).collect();
+        assert_eq!(flagged, ["and", "xyzzy"]);
+        assert_eq!(WeightedVote::new().with(Chunks, 1.0).classify_text(text).len(), 6);
+    }
+
+    #[test]
+    fn test_explanations_come_from_the_deciding_classifier() {
+        let speller = english::speller();
+        let context = ContextClassifier::new(&speller);
+        let rules = IpsumClassifier::new(&speller);
+        let text = "Log in to the page. Duis aute irure in reprehenderit voluptate, tomatox.";
+        let classifiers: [&dyn Classifier; 7] = [
+            &speller,
+            &DictionaryClassifier::new(&speller),
+            &rules,
+            &CorpusClassifier::new(),
+            &context,
+            &Any::new().with(&context).with(CorpusClassifier::new()),
+            &WeightedVote::new().with(&speller, 1.0).with(&rules, 2.0),
+        ];
+        for classifier in classifiers {
+            let explained: Vec<(Range<usize>, IpsumStatus)> =
+                classifier.explain_text(text).into_iter().map(|(range, explanation)| (range, explanation.status)).collect();
+            assert_eq!(explained, classifier.classify_text(text));
+            for word in ["in", "tomatox", "lorem", "page"] {
+                assert_eq!(classifier.explain_word(word).status, classifier.classify_word(word), "{}", word);
+            }
+        }
+
+        // The context override explains itself, alone and through a combination.
+        let rule = |classifier: &dyn Classifier, i: usize| classifier.explain_text(text)[i].1.rule;
+        assert_eq!(rule(&rules, 1), Rule::CorpusWord);
+        assert_eq!(rule(&context, 1), Rule::Context { filler: 0, real: 4 });
+        assert_eq!(rule(&context, 8), Rule::Context { filler: 6, real: 0 });
+        let all = All::new().with(&context).with(CorpusClassifier::new());
+        assert_eq!(rule(&all, 1), Rule::Context { filler: 0, real: 4 });
+        assert_eq!(rule(&all, 5), Rule::CorpusWord);
+        assert_eq!(rule(&CorpusClassifier::new(), 11), Rule::NotCorpusWord);
+        assert_eq!(rule(&DictionaryClassifier::new(&speller), 11), Rule::NotInDictionary);
+        assert_eq!(speller.explain_word("page").frequency, speller.n_words.get("page").copied());
+        assert_eq!(context.explain_word("in").rule, Rule::Context { filler: 0, real: 0 });
+        assert_eq!(Chunks.explain_word("lorem").rule, Rule::Unexplained);
+    }
+
+    #[test]
+    fn test_filters_take_any_classifier() {
+        let speller = english::speller();
+        let text = "Log in to the page. Duis aute irure in reprehenderit voluptate.";
+        // Word by word, the corpus rules flag the English "in" too.
+        assert_eq!(remove_ipsum(&IpsumClassifier::new(&speller), text), "Log to the page.");
+        // In context it stays, and so it does when combined with another classifier.
+        let context = ContextClassifier::new(&speller);
+        assert_eq!(remove_ipsum(&context, text), "Log in to the page.");
+        let combined = All::new().with(&context).with(CorpusClassifier::new());
+        assert_eq!(remove_ipsum(&combined, text), "Log in to the page.");
+        assert_eq!(scan_text(&combined, text), context.classify_text(text));
+
+        let boxed: Box<dyn Classifier> = Box::new(CorpusClassifier::new());
+        assert_eq!(scan_text(&boxed, "lorem tomato").iter().filter(|(_, s)| s.is_filler()).count(), 1);
+    }
+
+    #[test]
+    fn test_contractions_are_classified_by_their_stem() {
+        let speller = english::speller();
+        let text = "I don't know what you're doing, it's fine. Can’t we? Lorem ipsum dolor sit amet.";
+        let kept = "I don't know what you're doing, it's fine. Can’t we?";
+        assert_eq!(remove_ipsum(&ContextClassifier::new(&speller), text), kept);
+        assert_eq!(remove_ipsum(&IpsumClassifier::new(&speller), text), kept);
+        assert_eq!(remove_ipsum(&speller, text), kept);
+        // A clitic does not make filler real.
+        assert!(IpsumClassifier::new(&speller).classify_word("amet's").is_filler());
+    }
+}
@@ -0,0 +1,202 @@
+//! Thresholds and word lists shared by the classifiers.
+//!
+//! A [`ClassifierConfig`] collects the numbers the classifiers used to hard-code: the share of
+//! a word its longest dictionary substring must cover to make it [`Partial`](crate::ipsum::IpsumStatus::Partial),
+//! the Jaccard similarity at which [`detect_lorem_ipsum`](crate::lorem::detect_lorem_ipsum)
+//! flags a text, and how long substrings and words must be to count, along with case
+//! handling and a list of words that are never filler. It can be loaded from a TOML file,
+//! where every key is optional:
+//!
+//! ```toml
+//! min_partial_ratio = 0.8
+//! min_substring_len = 3
+//! ignore = ["Acme", "kubectl"]
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// An error raised while loading a [`ClassifierConfig`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not valid TOML, or does not describe a configuration.
+    Toml(toml::de::Error),
+    /// A value is out of range.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(_) => write!(f, "configuration file I/O failed"),
+            ConfigError::Toml(_) => write!(f, "invalid TOML configuration"),
+            ConfigError::Invalid(reason) => write!(f, "invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Toml(err) => Some(err),
+            ConfigError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+/// Tunes how the classifiers decide which words are filler.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClassifierConfig {
+    /// The share of a word's characters that its longest dictionary substring must cover for
+    /// the word to be partly real rather than filler. [`CorpusClassifier`](crate::classifier::CorpusClassifier)
+    /// uses it for corpus substrings the same way.
+    pub min_partial_ratio: f64,
+    /// The Jaccard similarity above which [`detect_lorem_ipsum`](crate::lorem::detect_lorem_ipsum)
+    /// flags a text.
+    pub jaccard_threshold: f64,
+    /// Substrings with fewer characters than this are not looked for inside words.
+    pub min_substring_len: usize,
+    /// Words with fewer characters than this are always real.
+    pub min_word_len: usize,
+    /// Whether case matters. The corpus and dictionaries are lowercase, so with this set
+    /// "Lorem" is not a corpus word.
+    pub case_sensitive: bool,
+    /// Words that are always real, such as product names that look Latin.
+    pub ignore: Vec<String>,
+}
+
+impl Default for ClassifierConfig {
+    /// The thresholds the classifiers have always used: a 70% partial ratio, a 0.3 Jaccard
+    /// threshold, substrings and words of any length, no case and nothing ignored.
+    fn default() -> Self {
+        ClassifierConfig {
+            min_partial_ratio: 0.7,
+            jaccard_threshold: 0.3,
+            min_substring_len: 1,
+            min_word_len: 1,
+            case_sensitive: false,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+impl ClassifierConfig {
+    /// Parses a configuration from TOML. Missing keys keep their defaults; unknown keys and
+    /// ratios outside `[0, 1]` are errors.
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let config: ClassifierConfig = toml::from_str(text).map_err(ConfigError::Toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Loads a configuration from a TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Checks that both ratios lie in `[0, 1]`.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (name, value) in [("min_partial_ratio", self.min_partial_ratio), ("jaccard_threshold", self.jaccard_threshold)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::Invalid(format!("{} must be between 0 and 1, got {}", name, value)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `word` is on the ignore list, ignoring case unless the configuration is case
+    /// sensitive.
+    pub fn is_ignored(&self, word: &str) -> bool {
+        if self.case_sensitive {
+            self.ignore.iter().any(|ignored| ignored == word)
+        } else {
+            let word = word.to_lowercase();
+            self.ignore.iter().any(|ignored| ignored.to_lowercase() == word)
+        }
+    }
+
+    /// Whether `word` is real whatever the corpus and dictionary say: it is ignored, or
+    /// shorter than [`min_word_len`](ClassifierConfig::min_word_len).
+    pub fn skips(&self, word: &str) -> bool {
+        word.chars().count() < self.min_word_len || self.is_ignored(word)
+    }
+
+    /// Whether a substring found inside a word is long enough to count.
+    pub(crate) fn is_long_enough(&self, substring: &str) -> bool {
+        substring.chars().count() >= self.min_substring_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifier::{Classifier, CorpusClassifier, DictionaryClassifier};
+    use crate::context::ContextClassifier;
+    use crate::english;
+    use crate::ipsum::{remove_ipsum, scan_text, IpsumStatus};
+    use crate::ipsum_classifier::IpsumClassifier;
+
+    #[test]
+    fn test_parse_toml() {
+        let config = ClassifierConfig::from_toml("min_partial_ratio = 0.8\nmin_substring_len = 3\nignore = [\"Acme\"]\n").unwrap();
+        assert_eq!(
+            config,
+            ClassifierConfig { min_partial_ratio: 0.8, min_substring_len: 3, ignore: vec!["Acme".to_string()], ..ClassifierConfig::default() }
+        );
+        assert_eq!(ClassifierConfig::from_toml("").unwrap(), ClassifierConfig::default());
+        assert!(matches!(ClassifierConfig::from_toml("min_ratio = 0.8"), Err(ConfigError::Toml(_))));
+        assert!(matches!(ClassifierConfig::from_toml("jaccard_threshold = 1.5"), Err(ConfigError::Invalid(_))));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(ClassifierConfig::load(file.path()).unwrap(), config);
+        assert!(matches!(ClassifierConfig::load(file.path().with_extension("missing")), Err(ConfigError::Io(_))));
+    }
+
+    #[test]
+    fn test_classifiers_follow_the_config() {
+        let speller = english::speller();
+        let strict = ClassifierConfig { min_partial_ratio: 0.9, ..ClassifierConfig::default() };
+        // "peoplex" is 6/7 ≈ 0.86 "people".
+        assert!(matches!(IpsumClassifier::new(&speller).classify("peoplex"), IpsumStatus::Partial { .. }));
+        assert_eq!(IpsumClassifier::new(&speller).with_config(strict).classify("peoplex"), IpsumStatus::FullyIpsum);
+
+        // Short substrings stop counting: "xyzat" only contains "at".
+        let long = ClassifierConfig { min_substring_len: 3, ..ClassifierConfig::default() };
+        let dictionary = DictionaryClassifier::new(&speller);
+        assert!(matches!(dictionary.classify_word("xyzat"), IpsumStatus::Partial { .. }));
+        assert_eq!(dictionary.with_config(long.clone()).classify_word("xyzat"), IpsumStatus::FullyIpsum);
+        assert_eq!(IpsumClassifier::new(&speller).with_config(long).classify("xyzzy"), IpsumStatus::NotLorem);
+
+        let ignoring = ClassifierConfig { ignore: vec!["Dolor".to_string()], min_word_len: 3, ..ClassifierConfig::default() };
+        let text = "Lorem ipsum dolor sit amet ut.";
+        let statuses = |classifier: &dyn Classifier| scan_text(classifier, text).into_iter().map(|(_, s)| s.is_filler()).collect::<Vec<_>>();
+        for classifier in [
+            &IpsumClassifier::new(&speller).with_config(ignoring.clone()) as &dyn Classifier,
+            &CorpusClassifier::new().with_config(ignoring.clone()),
+            &ContextClassifier::new(&speller).with_config(ignoring.clone()),
+        ] {
+            assert_eq!(statuses(classifier), [true, true, false, true, true, false]);
+        }
+        assert_eq!(remove_ipsum(&IpsumClassifier::new(&speller).with_config(ignoring), text), "dolor ut.");
+
+        let sensitive = ClassifierConfig { case_sensitive: true, ..ClassifierConfig::default() };
+        let corpus = CorpusClassifier::new().with_config(sensitive);
+        assert_eq!(corpus.classify_word("Lorem"), IpsumStatus::NotLorem);
+        assert_eq!(corpus.classify_word("lorem"), IpsumStatus::FullyIpsum);
+    }
+}
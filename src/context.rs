@@ -0,0 +1,243 @@
+//! Telling Latin filler from English where the two share words.
+//!
+//! Some words of the [`dolor::corpus`](crate::dolor::corpus) are also everyday English: "in",
+//! "do", "a", "at", "sit", or short enough to turn up in English text as names, abbreviations
+//! or Latin tags ("et", "ex", "id", "est", "mi", "leo"). Because
+//! [`classify_word`](crate::ipsum_classifier::classify_word) checks the corpus first, it calls
+//! them filler wherever they appear. A [`ContextClassifier`] classifies such
+//! [`AMBIGUOUS_WORDS`] by their neighbours instead: one is filler only when most of the
+//! unambiguous words around it in its sentence are filler too.
+
+use std::ops::Range;
+
+use spellcheck::Speller;
+
+use crate::classifier::Classifier;
+use crate::config::ClassifierConfig;
+use crate::ipsum::IpsumStatus;
+use crate::ipsum_classifier::{Explanation, IpsumClassifier, Rule};
+use crate::tokenizer::{self, TokenKind};
+
+/// Corpus words that are also common outside filler, sorted.
+pub const AMBIGUOUS_WORDS: &[&str] = &["a", "ad", "at", "do", "est", "et", "ex", "id", "in", "leo", "mi", "non", "sed", "sit"];
+
+/// Whether `word` is one of the [`AMBIGUOUS_WORDS`], ignoring case.
+pub fn is_ambiguous(word: &str) -> bool {
+    AMBIGUOUS_WORDS.binary_search(&word.to_lowercase().as_str()).is_ok()
+}
+
+/// Controls how much context decides an ambiguous word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextOptions {
+    /// How many words on each side of an ambiguous word are consulted. The window stops at the
+    /// ends of the word's sentence, and other ambiguous words in it are skipped, since they say
+    /// nothing either way.
+    pub window: usize,
+}
+
+impl Default for ContextOptions {
+    /// Three words on each side.
+    fn default() -> Self {
+        ContextOptions { window: 3 }
+    }
+}
+
+/// Classifies the words of a text, deciding [`AMBIGUOUS_WORDS`] by their neighbours.
+#[derive(Debug, Clone)]
+pub struct ContextClassifier {
+    classifier: IpsumClassifier,
+    options: ContextOptions,
+}
+
+impl ContextClassifier {
+    /// Creates a classifier over the dictionary of `speller`, with the default window.
+    pub fn new(speller: &Speller) -> Self {
+        ContextClassifier { classifier: IpsumClassifier::new(speller), options: ContextOptions::default() }
+    }
+
+    /// Sets how much context is consulted.
+    pub fn with_options(mut self, options: ContextOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the thresholds, word lists and case handling the words are classified with.
+    pub fn with_config(mut self, config: ClassifierConfig) -> Self {
+        self.classifier = self.classifier.with_config(config);
+        self
+    }
+
+    /// Classifies every word of `text` like [`scan_text`](crate::ipsum::scan_text), returning
+    /// each word's byte range and status. Unambiguous words are classified on their own.
+    /// An ambiguous word is [`IpsumStatus::FullyIpsum`] if more of the unambiguous words in
+    /// its window are filler than are real, and [`IpsumStatus::NotLorem`] otherwise, so
+    /// without context it is taken to be English.
+    pub fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        self.explain_text(text).into_iter().map(|(range, explanation)| (range, explanation.status)).collect()
+    }
+
+    /// [`ContextClassifier::classify_text`], with each word's status explained. Ambiguous
+    /// words decided by their neighbours are explained by [`Rule::Context`].
+    pub fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        // Sentences are found over every word, numbers included, before numbers are dropped.
+        let all_words: Vec<_> = tokenizer::words(text).collect();
+        let all_sentences = tokenizer::sentence_ids(text, &all_words);
+        let (words, sentences): (Vec<_>, Vec<_>) = all_words
+            .into_iter()
+            .zip(all_sentences)
+            .filter(|(word, _)| word.kind == TokenKind::Word)
+            .unzip();
+        let explanations: Vec<Explanation> = words.iter().map(|word| self.classifier.explain(word.text)).collect();
+        // Ambiguous words the configuration keeps real stay real.
+        let ambiguous: Vec<bool> = words
+            .iter()
+            .zip(&explanations)
+            .map(|(word, explanation)| is_ambiguous(word.text) && explanation.status.is_filler())
+            .collect();
+
+        (0..words.len())
+            .map(|i| {
+                if !ambiguous[i] {
+                    return (words[i].bytes.clone(), explanations[i].clone());
+                }
+                let window = i.saturating_sub(self.options.window)..(i + self.options.window + 1).min(words.len());
+                let (mut filler, mut real) = (0, 0);
+                for j in window.filter(|&j| j != i && !ambiguous[j] && sentences[j] == sentences[i]) {
+                    match explanations[j].status {
+                        IpsumStatus::FullyIpsum => filler += 1,
+                        IpsumStatus::NotLorem => real += 1,
+                        IpsumStatus::Partial { .. } => {}
+                    }
+                }
+                let status = if filler > real { IpsumStatus::FullyIpsum } else { IpsumStatus::NotLorem };
+                (words[i].bytes.clone(), Explanation::new(status, Rule::Context { filler, real }, self.classifier.config()))
+            })
+            .collect()
+    }
+}
+
+impl Classifier for ContextClassifier {
+    /// A word on its own has no context: [`AMBIGUOUS_WORDS`] are real, others are classified
+    /// as usual.
+    fn classify_word(&self, word: &str) -> IpsumStatus {
+        self.explain_word(word).status
+    }
+
+    /// [`ContextClassifier::classify_text`].
+    fn classify_text(&self, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+        ContextClassifier::classify_text(self, text)
+    }
+
+    /// Like [`classify_word`](Classifier::classify_word), an ambiguous word on its own is
+    /// real by [`Rule::Context`] with no neighbours either way.
+    fn explain_word(&self, word: &str) -> Explanation {
+        let explanation = self.classifier.explain(word);
+        if is_ambiguous(word) && explanation.status.is_filler() {
+            return Explanation::new(IpsumStatus::NotLorem, Rule::Context { filler: 0, real: 0 }, self.classifier.config());
+        }
+        explanation
+    }
+
+    /// [`ContextClassifier::explain_text`].
+    fn explain_text(&self, text: &str) -> Vec<(Range<usize>, Explanation)> {
+        ContextClassifier::explain_text(self, text)
+    }
+}
+
+/// Classifies the words of `text` with a [`ContextClassifier`] over `speller`.
+pub fn classify_in_context(speller: &Speller, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+    ContextClassifier::new(speller).classify_text(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::english;
+    use crate::ipsum_classifier::classify_word;
+
+    /// English that uses the ambiguous words and must never be reported as filler.
+    const ENGLISH: &[&str] = &[
+        "Please log in to do the work.",
+        "We put an ad in the paper at the end of the week.",
+        "What do you do in the morning?",
+        "Sit down and tell me about it.",
+        "She gave a talk at the school in the city.",
+        "Do not put your id in the box.",
+        "The water is in the house, not at the door.",
+        "I think you should sit in the front.",
+        "He is in a small town and will be at home.",
+        "They do what they can to help the people in need.",
+        "Tell me what you did at work.",
+        "Do not call your ex at work.",
+        "Come in and sit at the table.",
+        "Log in to do an ad.",
+    ];
+
+    const FILLER: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+
+    fn flagged<'t>(speller: &Speller, text: &'t str) -> Vec<&'t str> {
+        classify_in_context(speller, text)
+            .into_iter()
+            .filter(|(_, status)| *status != IpsumStatus::NotLorem)
+            .map(|(range, _)| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_english_stays_clean() {
+        let speller = english::speller();
+        for sentence in ENGLISH {
+            assert!(flagged(&speller, sentence).is_empty(), "{}: {:?}", sentence, flagged(&speller, sentence));
+            // Filler in the next sentence does not outvote the English.
+            for text in [format!("{} {}", sentence, FILLER), format!("{} {}", FILLER, sentence), format!("{}\n\n{}", sentence, FILLER)] {
+                let english = text.find(sentence).unwrap()..text.find(sentence).unwrap() + sentence.len();
+                let wrong: Vec<&str> = classify_in_context(&speller, &text)
+                    .into_iter()
+                    .filter(|(range, status)| english.contains(&range.start) && *status != IpsumStatus::NotLorem)
+                    .map(|(range, _)| &text[range])
+                    .collect();
+                assert!(wrong.is_empty(), "{}: {:?}", text, wrong);
+            }
+        }
+        // Without context, the corpus lookup would have flagged the shared words.
+        let words: Vec<&str> = ENGLISH.iter().flat_map(|s| s.split(|c: char| !c.is_alphabetic())).collect();
+        assert!(words.iter().any(|w| is_ambiguous(w) && classify_word(&speller, w) == IpsumStatus::FullyIpsum));
+    }
+
+    #[test]
+    fn test_filler_keeps_its_shared_words() {
+        let speller = english::speller();
+        let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+        let statuses = classify_in_context(&speller, text);
+        for (range, status) in &statuses {
+            assert_eq!(*status, IpsumStatus::FullyIpsum, "{}", &text[range.clone()]);
+        }
+
+        // The same word goes either way depending on its neighbours.
+        let mixed = "Log in to the page. Duis aute irure in reprehenderit voluptate.";
+        let ins: Vec<IpsumStatus> = classify_in_context(&speller, mixed)
+            .into_iter()
+            .filter(|(range, _)| &mixed[range.clone()] == "in")
+            .map(|(_, status)| status)
+            .collect();
+        assert_eq!(ins, [IpsumStatus::NotLorem, IpsumStatus::FullyIpsum]);
+        // A sentence ending after a number still separates the neighbours.
+        assert!(flagged(&speller, "Lorem ipsum dolor sit amet 2. In the house.").iter().all(|&w| w != "In"));
+
+        // Ambiguous words alone are taken to be English.
+        assert!(flagged(&speller, "in at do").is_empty());
+        // A wider window weighs more neighbours.
+        let text = "lorem ipsum dolor in the house";
+        assert_eq!(classify_in_context(&speller, text)[3].1, IpsumStatus::FullyIpsum);
+        let narrow = ContextClassifier::new(&speller).with_options(ContextOptions { window: 1 });
+        assert_eq!(narrow.classify_text(text)[3].1, IpsumStatus::NotLorem);
+    }
+
+    #[test]
+    fn test_ambiguous_words_are_sorted_corpus_words() {
+        assert!(AMBIGUOUS_WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        let corpus = crate::dolor::corpus();
+        assert!(AMBIGUOUS_WORDS.iter().all(|word| corpus.contains(word)));
+        assert!(is_ambiguous("In") && !is_ambiguous("lorem"));
+    }
+}
@@ -0,0 +1,565 @@
+//! A deterministic Lorem Ipsum detector that returns a calibrated score.
+//!
+//! Unlike [`crate::lorem::detect_lorem_ipsum`], which compares the input against a randomly
+//! generated sample, this detector scores every word by its log-likelihood ratio under two
+//! class models: a lorem model (the words of a [`MarkovModel`] plus [`crate::dolor::corpus`])
+//! and a background English model ([`crate::english::common_words`]). Each class model mixes a
+//! word-frequency estimate with a character trigram model, so words neither side has seen are
+//! still judged by how Latin- or English-looking their spelling is.
+//!
+//! The mean ratio over the text is passed through the logistic function, giving a score in
+//! `[0, 1]` that reads as the probability a word of the text is filler, assuming equal priors.
+//! The default threshold of 0.5 is therefore the point where filler and English are equally
+//! likely; [`LoremDetector::calibrate_threshold`] picks a cutoff from labeled examples instead.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::config::ClassifierConfig;
+use crate::dolor;
+use crate::english;
+use crate::ipsum::{classify_word_with_matcher_and_config, IpsumStatus};
+use crate::matcher::WordMatcher;
+use crate::model::MarkovModel;
+use crate::tokenizer::{self, TokenKind};
+
+/// How much of a word's probability comes from its frequency rather than its spelling.
+const WORD_WEIGHT: f64 = 0.5;
+
+/// Per-word log-likelihood ratios are clamped to this magnitude so a single very unusual
+/// word cannot decide the verdict for a whole text.
+const MAX_WORD_LLR: f64 = 8.0;
+
+/// Add-k smoothing constant for the character trigram model.
+const CHAR_SMOOTHING: f64 = 0.1;
+
+/// The alphabet size assumed when smoothing: 26 letters, an end marker and "anything else".
+const CHAR_ALPHABET: f64 = 28.0;
+
+/// How far span detection shifts the log-likelihood ratio of a word that the dictionary rules
+/// classify as [`IpsumStatus::NotLorem`], i.e. one the caller's dictionary knows. There is no
+/// matching shift towards filler for words the dictionary cannot explain at all: identifiers
+/// such as "npm" or "ctx" and rare names are missing from most dictionaries too, so they are
+/// left to the character models.
+const STATUS_LLR: f64 = 2.0;
+
+/// Marks the start and end of a word in the character model.
+const BOUNDARY: char = '^';
+
+/// Reduces a token to the form both class models are trained on: lowercase, without
+/// surrounding punctuation. Returns `None` unless what remains is at least two letters,
+/// optionally joined by apostrophes or hyphens; numbers, single letters and code such as
+/// `rng.unwrap()` carry no evidence.
+pub(crate) fn normalize_word(token: &str) -> Option<String> {
+    let word = token.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase();
+    let is_word = word.chars().count() >= 2
+        && word.chars().all(|c| c.is_alphabetic() || matches!(c, '\'' | '’' | '-'));
+    is_word.then_some(word)
+}
+
+/// A character trigram model over words, with `^` marking both ends.
+#[derive(Debug, Clone, Default)]
+struct CharTrigrams {
+    trigrams: HashMap<(char, char, char), f64>,
+    contexts: HashMap<(char, char), f64>,
+}
+
+impl CharTrigrams {
+    fn add(&mut self, word: &str, weight: f64) {
+        let chars: Vec<char> = [BOUNDARY, BOUNDARY]
+            .into_iter()
+            .chain(word.chars())
+            .chain([BOUNDARY])
+            .collect();
+        for w in chars.windows(3) {
+            *self.trigrams.entry((w[0], w[1], w[2])).or_default() += weight;
+            *self.contexts.entry((w[0], w[1])).or_default() += weight;
+        }
+    }
+
+    fn log_prob(&self, word: &str) -> f64 {
+        let chars: Vec<char> = [BOUNDARY, BOUNDARY]
+            .into_iter()
+            .chain(word.chars())
+            .chain([BOUNDARY])
+            .collect();
+        chars
+            .windows(3)
+            .map(|w| {
+                let trigram = self.trigrams.get(&(w[0], w[1], w[2])).copied().unwrap_or(0.0);
+                let context = self.contexts.get(&(w[0], w[1])).copied().unwrap_or(0.0);
+                ((trigram + CHAR_SMOOTHING) / (context + CHAR_SMOOTHING * CHAR_ALPHABET)).ln()
+            })
+            .sum()
+    }
+}
+
+/// One side of the likelihood ratio: word frequencies backed off to spelling.
+#[derive(Debug, Clone, Default)]
+struct ClassModel {
+    words: HashMap<String, f64>,
+    total: f64,
+    chars: CharTrigrams,
+}
+
+impl ClassModel {
+    fn new<'a>(words: impl IntoIterator<Item = (&'a str, f64)>) -> Self {
+        let mut model = ClassModel::default();
+        for (word, count) in words {
+            if let Some(word) = normalize_word(word) {
+                *model.words.entry(word).or_default() += count;
+                model.total += count;
+            }
+        }
+        // The spelling model learns from each distinct word once, so a handful of very
+        // frequent words do not dominate it.
+        for word in model.words.keys() {
+            model.chars.add(word, 1.0);
+        }
+        model
+    }
+
+    fn log_prob(&self, word: &str) -> f64 {
+        let frequency = match self.words.get(word) {
+            Some(count) if self.total > 0.0 => count / self.total,
+            _ => 0.0,
+        };
+        (WORD_WEIGHT * frequency + (1.0 - WORD_WEIGHT) * self.chars.log_prob(word).exp()).ln()
+    }
+}
+
+/// The outcome of running a [`LoremDetector`] over a text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    /// The probability, in `[0, 1]`, that a word of the text is filler.
+    pub score: f64,
+    /// The mean per-word log-likelihood ratio of filler over English.
+    pub log_likelihood_ratio: f64,
+    /// The number of words that were scored.
+    pub words: usize,
+    /// Whether `score` reached the detector's threshold.
+    pub is_lorem: bool,
+}
+
+/// How [`LoremDetector::detect_spans`] groups words before scoring them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanGranularity {
+    /// Each word takes the score of its sentence. Sentences end at `.`, `!` or `?`
+    /// and at blank lines, also after a number.
+    Sentences,
+    /// Each word takes the score of a window of this many words centred on it.
+    Window(usize),
+}
+
+/// A region of a document that looks like filler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillerSpan {
+    /// Byte range of the region in the scanned text, from the start of its first word
+    /// to the end of its last.
+    pub range: Range<usize>,
+    /// The score of the region's words taken together, in `[0, 1]`.
+    pub score: f64,
+    /// The number of words that were scored in the region.
+    pub words: usize,
+}
+
+/// Scores text by how much more likely it is under a lorem model than under English.
+#[derive(Debug, Clone)]
+pub struct LoremDetector {
+    lorem: ClassModel,
+    english: ClassModel,
+    threshold: f64,
+    config: ClassifierConfig,
+}
+
+impl Default for LoremDetector {
+    /// A detector whose lorem side is [`dolor::corpus`] alone.
+    fn default() -> Self {
+        LoremDetector::with_lorem_words(Vec::new())
+    }
+}
+
+impl LoremDetector {
+    /// The default cutoff: filler and English are equally likely.
+    pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+    fn with_lorem_words(mut lorem_words: Vec<(String, f64)>) -> Self {
+        let mut corpus: Vec<&str> = dolor::corpus().into_iter().collect();
+        corpus.sort_unstable();
+        lorem_words.extend(corpus.into_iter().map(|w| (w.to_string(), 1.0)));
+        let mut english: Vec<(&str, u32)> = english::word_frequencies().into_iter().collect();
+        english.sort_unstable();
+        LoremDetector {
+            lorem: ClassModel::new(lorem_words.iter().map(|(w, c)| (w.as_str(), *c))),
+            english: ClassModel::new(english.iter().map(|(w, c)| (*w, *c as f64))),
+            threshold: Self::DEFAULT_THRESHOLD,
+            config: ClassifierConfig::default(),
+        }
+    }
+
+    /// Builds a detector whose lorem side combines the words of `model` with [`dolor::corpus`].
+    pub fn from_model(model: &MarkovModel) -> Self {
+        let mut words: Vec<(String, f64)> = model
+            .word_counts()
+            .into_iter()
+            .map(|(word, count)| (word, count as f64))
+            .collect();
+        // Sort so floating-point sums do not depend on HashMap iteration order.
+        words.sort_by(|a, b| a.0.cmp(&b.0));
+        LoremDetector::with_lorem_words(words)
+    }
+
+    /// Builds a detector from seed text; shorthand for a first-order model of the seed.
+    pub fn from_seed(seed: &str) -> Self {
+        LoremDetector::from_model(&MarkovModel::train(seed, 1))
+    }
+
+    /// Sets the score at or above which a text is reported as lorem.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// The score at or above which a text is reported as lorem.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Sets the word lists, minimum lengths and case handling that decide which words are
+    /// always real, and how span detection checks words against the dictionary.
+    pub fn with_config(mut self, config: ClassifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The word lists, minimum lengths and case handling.
+    pub fn config(&self) -> &ClassifierConfig {
+        &self.config
+    }
+
+    /// The clamped log-likelihood ratio of filler over English for a single token,
+    /// or `None` if the token has no letters.
+    pub fn word_log_likelihood_ratio(&self, token: &str) -> Option<f64> {
+        let word = normalize_word(token)?;
+        let ratio = self.lorem.log_prob(&word) - self.english.log_prob(&word);
+        Some(ratio.clamp(-MAX_WORD_LLR, MAX_WORD_LLR))
+    }
+
+    /// Scores the words of a text, as split by [`tokenizer::words`]. Words the configuration
+    /// skips are left out. Texts without any words score 0.
+    pub fn detect(&self, text: &str) -> Detection {
+        let ratios: Vec<f64> = tokenizer::words(text)
+            .filter(|word| !self.config.skips(word.text))
+            .filter_map(|word| self.word_log_likelihood_ratio(word.text))
+            .collect();
+        if ratios.is_empty() {
+            return Detection { score: 0.0, log_likelihood_ratio: 0.0, words: 0, is_lorem: false };
+        }
+        let mean = ratios.iter().sum::<f64>() / ratios.len() as f64;
+        let score = logistic(mean);
+        Detection {
+            score,
+            log_likelihood_ratio: mean,
+            words: ratios.len(),
+            is_lorem: score >= self.threshold,
+        }
+    }
+
+    /// Shorthand for `self.detect(text).score`.
+    pub fn score(&self, text: &str) -> f64 {
+        self.detect(text).score
+    }
+
+    /// Picks the threshold that best separates labeled filler from labeled real text.
+    ///
+    /// Every midpoint between adjacent scores is tried and the one with the highest balanced
+    /// accuracy wins; among equally good cutoffs the middle one is returned, which leaves the
+    /// widest margin on both sides. Returns the current threshold if either list is empty.
+    pub fn calibrate_threshold(&self, lorem_examples: &[&str], real_examples: &[&str]) -> f64 {
+        if lorem_examples.is_empty() || real_examples.is_empty() {
+            return self.threshold;
+        }
+        let lorem: Vec<f64> = lorem_examples.iter().map(|t| self.score(t)).collect();
+        let real: Vec<f64> = real_examples.iter().map(|t| self.score(t)).collect();
+        let mut scores: Vec<f64> = lorem.iter().chain(&real).copied().collect();
+        scores.sort_by(f64::total_cmp);
+        scores.dedup();
+        let mut candidates = vec![0.0];
+        candidates.extend(scores.windows(2).map(|w| (w[0] + w[1]) / 2.0));
+        candidates.push(1.0);
+
+        let accuracy = |t: f64| {
+            let hits = lorem.iter().filter(|&&s| s >= t).count() as f64 / lorem.len() as f64;
+            let rejections = real.iter().filter(|&&s| s < t).count() as f64 / real.len() as f64;
+            (hits + rejections) / 2.0
+        };
+        let best = candidates.iter().map(|&t| accuracy(t)).fold(0.0, f64::max);
+        let winners: Vec<f64> = candidates.into_iter().filter(|&t| accuracy(t) == best).collect();
+        winners[winners.len() / 2]
+    }
+
+    /// The words of `text` as [`scan_text`](crate::ipsum::scan_text) finds them, with their
+    /// log-likelihood ratios. Words `dictionary` knows lean English by [`STATUS_LLR`]; words
+    /// without letters and words the configuration skips are left out.
+    pub(crate) fn scored_words(&self, dictionary: &WordMatcher, text: &str) -> Vec<(Range<usize>, f64)> {
+        self.scored_sentence_words(dictionary, text).into_iter().map(|(range, ratio, _)| (range, ratio)).collect()
+    }
+
+    /// [`scored_words`](Self::scored_words) with the sentence each word belongs to. Sentences
+    /// are found over all the words of `text`, so one ending after a left-out word still ends.
+    fn scored_sentence_words(&self, dictionary: &WordMatcher, text: &str) -> Vec<(Range<usize>, f64, usize)> {
+        let words: Vec<_> = tokenizer::words(text).collect();
+        let sentences = tokenizer::sentence_ids(text, &words);
+        words
+            .into_iter()
+            .zip(sentences)
+            .filter(|(word, _)| word.kind == TokenKind::Word && !self.config.skips(word.text))
+            .filter_map(|(word, sentence)| {
+                let ratio = self.word_log_likelihood_ratio(word.text)?;
+                let nudge = match classify_word_with_matcher_and_config(dictionary, word.text, &self.config) {
+                    IpsumStatus::NotLorem => -STATUS_LLR,
+                    IpsumStatus::FullyIpsum | IpsumStatus::Partial { .. } => 0.0,
+                };
+                Some((word.bytes, ratio + nudge, sentence))
+            })
+            .collect()
+    }
+
+    /// Finds the regions of `text` that look like filler.
+    ///
+    /// Words come from [`scan_text`](crate::ipsum::scan_text), and words whose [`IpsumStatus`]
+    /// shows that `dictionary` knows them, under the detector's configuration, count as more
+    /// English. Compile the dictionary once with [`WordMatcher::from_speller`] and reuse it
+    /// across texts. Words it cannot explain are left to the detector's own models, since
+    /// identifiers and rare words are missing from most dictionaries too.
+    /// Every word is then scored together with its sentence or window, and runs of
+    /// consecutive words scoring at or above the threshold become one span, minus any words
+    /// at either end that lean English on their own.
+    pub fn detect_spans(&self, dictionary: &WordMatcher, text: &str, granularity: SpanGranularity) -> Vec<FillerSpan> {
+        let scored = self.scored_sentence_words(dictionary, text);
+        let words: Vec<(Range<usize>, f64)> = scored.iter().map(|(range, ratio, _)| (range.clone(), *ratio)).collect();
+        let groups: Vec<Range<usize>> = match granularity {
+            SpanGranularity::Sentences => {
+                let sentences = sentence_groups(&scored);
+                sentences
+                    .iter()
+                    .flat_map(|sentence| std::iter::repeat_n(sentence.clone(), sentence.len()))
+                    .collect()
+            }
+            SpanGranularity::Window(size) => {
+                let size = size.max(1);
+                (0..words.len())
+                    .map(|i| {
+                        let start = i.saturating_sub((size - 1) / 2).min(words.len().saturating_sub(size));
+                        start..(start + size).min(words.len())
+                    })
+                    .collect()
+            }
+        };
+        let mean = |group: &Range<usize>| {
+            words[group.clone()].iter().map(|(_, ratio)| ratio).sum::<f64>() / group.len() as f64
+        };
+
+        let mut spans = Vec::new();
+        let mut run: Option<usize> = None;
+        let flags = groups.iter().map(|group| logistic(mean(group)) >= self.threshold);
+        // A trailing `false` closes a run that reaches the last word.
+        for (i, flagged) in flags.chain([false]).enumerate() {
+            match (flagged, run) {
+                (true, None) => run = Some(i),
+                (false, Some(mut start)) => {
+                    // A sentence or window can drag neighbouring English words into the run;
+                    // trim them off the edges.
+                    let mut end = i;
+                    while start < end && words[start].1 < 0.0 {
+                        start += 1;
+                    }
+                    while start < end && words[end - 1].1 < 0.0 {
+                        end -= 1;
+                    }
+                    if start < end {
+                        spans.push(FillerSpan {
+                            range: words[start].0.start..words[end - 1].0.end,
+                            score: logistic(mean(&(start..end))),
+                            words: end - start,
+                        });
+                    }
+                    run = None;
+                }
+                _ => {}
+            }
+        }
+        spans
+    }
+}
+
+/// Splits scored words into their sentences, returned as index ranges into `words`.
+fn sentence_groups(words: &[(Range<usize>, f64, usize)]) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for i in 0..words.len() {
+        if words.get(i + 1).is_none_or(|next| next.2 != words[i].2) {
+            groups.push(start..i + 1);
+            start = i + 1;
+        }
+    }
+    groups
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipsum::scan_text;
+
+    const PASSAGE: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    const ENGLISH: &[&str] = &[
+        "This is a regular English sentence that should not be classified as Lorem Ipsum.",
+        "Please log in to your account before you update the payment settings.",
+        "The quarterly report shows revenue growth across every region we operate in.",
+        "Click the button below to download the latest version of the installer.",
+        "We fixed a bug where the dialog closed before the upload had finished.",
+    ];
+
+    const LOREM: &[&str] = &[
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
+        "Vestibulum ante ipsum primis in faucibus orci luctus et ultrices posuere cubilia curae.",
+        "Pellentesque habitant morbi tristique senectus et netus et malesuada fames ac turpis egestas.",
+        "Nullam quis risus eget urna mollis ornare vel eu leo.",
+        "Curabitur blandit tempus porttitor, donec ullamcorper nulla non metus auctor fringilla.",
+    ];
+
+    #[test]
+    fn test_separates_lorem_from_english() {
+        let detector = LoremDetector::from_seed(PASSAGE);
+        for text in LOREM {
+            let detection = detector.detect(text);
+            assert!(detection.is_lorem && detection.score > 0.9, "{:?} for {}", detection, text);
+        }
+        for text in ENGLISH {
+            let detection = detector.detect(text);
+            assert!(!detection.is_lorem && detection.score < 0.1, "{:?} for {}", detection, text);
+        }
+    }
+
+    #[test]
+    fn test_detection_is_deterministic() {
+        let text = "Lorem ipsum dolor sit amet and some English words mixed in.";
+        let first = LoremDetector::from_seed(PASSAGE).detect(text);
+        let second = LoremDetector::from_seed(PASSAGE).detect(text);
+        assert_eq!(first, second);
+        assert!((0.0..=1.0).contains(&first.score));
+    }
+
+    #[test]
+    fn test_threshold_is_configurable() {
+        let text = "Lorem ipsum dolor sit amet and some English words mixed in.";
+        let detector = LoremDetector::from_seed(PASSAGE);
+        let score = detector.score(text);
+        assert!(detector.clone().with_threshold(score).detect(text).is_lorem);
+        assert!(!detector.with_threshold(score + 0.01).detect(text).is_lorem);
+    }
+
+    #[test]
+    fn test_calibrate_threshold() {
+        let detector = LoremDetector::default();
+        let threshold = detector.calibrate_threshold(LOREM, ENGLISH);
+        let detector = detector.with_threshold(threshold);
+        assert!(LOREM.iter().all(|t| detector.detect(t).is_lorem));
+        assert!(ENGLISH.iter().all(|t| !detector.detect(t).is_lorem));
+    }
+
+    #[test]
+    fn test_detect_spans_finds_lorem_paragraph() {
+        let readme = format!(
+            "# Installer\n\n{}\n\n{}\n\n{}\n",
+            ENGLISH[3], PASSAGE, ENGLISH[4]
+        );
+        let detector = LoremDetector::from_seed(PASSAGE);
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let start = readme.find("Lorem").unwrap();
+        // Spans run from the first word to the last, so the final period is not included.
+        let expected = start..start + PASSAGE.len() - 1;
+        let spans = detector.detect_spans(&dictionary, &readme, SpanGranularity::Sentences);
+        assert_eq!(spans.len(), 1, "{:?}", spans);
+        assert_eq!(spans[0].range, expected);
+        assert!(spans[0].score > 0.9 && spans[0].words == 69, "{:?}", spans[0]);
+
+        // Windows near the edges mix in English words, so the region may lose a word or two.
+        let spans = detector.detect_spans(&dictionary, &readme, SpanGranularity::Window(5));
+        assert_eq!(spans.len(), 1, "{:?}", spans);
+        assert!(spans[0].range.start >= expected.start - 1 && spans[0].range.end <= expected.end + 1);
+        assert!(spans[0].words >= 66 && spans[0].score > 0.9, "{:?}", spans[0]);
+    }
+
+    #[test]
+    fn test_sentence_ends_after_a_number() {
+        let detector = LoremDetector::from_seed(PASSAGE);
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let numbered = format!("Lorem ipsum dolor sit amet 2. {}", ENGLISH[1]);
+        let spans = detector.detect_spans(&dictionary, &numbered, SpanGranularity::Sentences);
+        let plain = format!("Lorem ipsum dolor sit amet. {}", ENGLISH[1]);
+        assert_eq!(spans, detector.detect_spans(&dictionary, &plain, SpanGranularity::Sentences));
+        assert_eq!(spans.len(), 1, "{:?}", spans);
+        assert_eq!(&numbered[spans[0].range.clone()], "Lorem ipsum dolor sit amet");
+    }
+
+    #[test]
+    fn test_detect_spans_without_filler() {
+        let text = ENGLISH.join(" ");
+        let detector = LoremDetector::from_seed(PASSAGE);
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        for granularity in [SpanGranularity::Sentences, SpanGranularity::Window(5)] {
+            assert!(detector.detect_spans(&dictionary, &text, granularity).is_empty());
+        }
+        assert!(detector.detect_spans(&dictionary, "", SpanGranularity::Window(0)).is_empty());
+    }
+
+    #[test]
+    fn test_only_known_words_are_nudged() {
+        let detector = LoremDetector::from_seed(PASSAGE);
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        // The dictionary finds nothing at all in "npm", "ctx" or "gcc".
+        let text = "Run npm with the ctx flag before you push to the gcc build.";
+        let statuses = scan_text(&dictionary, text);
+        assert_eq!(statuses.iter().filter(|(_, status)| *status == IpsumStatus::FullyIpsum).count(), 3, "{:?}", statuses);
+        for ((range, ratio), (_, status)) in detector.scored_words(&dictionary, text).into_iter().zip(statuses) {
+            let own = detector.word_log_likelihood_ratio(&text[range]).unwrap();
+            let expected = if status == IpsumStatus::NotLorem { own - STATUS_LLR } else { own };
+            assert_eq!(ratio, expected);
+        }
+        assert!(detector.detect_spans(&dictionary, text, SpanGranularity::Sentences).is_empty());
+    }
+
+    #[test]
+    fn test_config_is_applied() {
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let text = "Vestibulum ante ipsum primis in faucibus orci luctus et ultrices posuere cubilia curae.";
+        let detector = LoremDetector::from_seed(PASSAGE);
+        assert_eq!(detector.detect_spans(&dictionary, text, SpanGranularity::Sentences).len(), 1);
+
+        // Ignored words are always real, so they are left out of scores and spans.
+        let words: Vec<String> = text.split(|c: char| !c.is_alphabetic()).map(str::to_string).collect();
+        let ignoring = detector.clone().with_config(ClassifierConfig { ignore: words, ..ClassifierConfig::default() });
+        assert_eq!(ignoring.detect(text).words, 0);
+        assert!(ignoring.detect_spans(&dictionary, text, SpanGranularity::Sentences).is_empty());
+        let short = detector.with_config(ClassifierConfig { min_word_len: 6, ..ClassifierConfig::default() });
+        assert_eq!(short.detect(text).words, 7);
+        assert_eq!(short.scored_words(&dictionary, text).len(), 7);
+        // Case-sensitive matching no longer finds "The" in the lowercase dictionary.
+        let case_sensitive = LoremDetector::from_seed(PASSAGE).with_config(ClassifierConfig { case_sensitive: true, ..ClassifierConfig::default() });
+        let the = case_sensitive.word_log_likelihood_ratio("The").unwrap();
+        assert_eq!(case_sensitive.scored_words(&dictionary, "The")[0].1, the);
+        assert_eq!(LoremDetector::from_seed(PASSAGE).scored_words(&dictionary, "The")[0].1, the - STATUS_LLR);
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let detection = LoremDetector::default().detect(" -- 42 ");
+        assert_eq!(detection, Detection { score: 0.0, log_likelihood_ratio: 0.0, words: 0, is_lorem: false });
+    }
+}
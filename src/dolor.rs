@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::matcher::WordMatcher;
 
 /// Returns a HashSet containing the "dolar corpus" collection of words commonly found in Lorem Ipsum text.
 ///
@@ -33,3 +36,28 @@ pub fn corpus() -> HashSet<&'static str> {
     words.iter().cloned().collect()
 }
 
+/// Returns the [`corpus`] compiled into a [`WordMatcher`], built on first use and shared.
+pub fn corpus_matcher() -> &'static WordMatcher {
+    static MATCHER: OnceLock<WordMatcher> = OnceLock::new();
+    MATCHER.get_or_init(|| {
+        let mut words: Vec<&str> = corpus().into_iter().collect();
+        words.sort_unstable();
+        WordMatcher::new(words)
+    })
+}
+
+/// Returns the [`corpus`] grouped by length in characters, each group sorted, built on first use
+/// and shared.
+pub fn corpus_by_length() -> &'static BTreeMap<usize, Vec<&'static str>> {
+    static BY_LENGTH: OnceLock<BTreeMap<usize, Vec<&'static str>>> = OnceLock::new();
+    BY_LENGTH.get_or_init(|| {
+        let mut by_length: BTreeMap<usize, Vec<&'static str>> = BTreeMap::new();
+        for word in corpus() {
+            by_length.entry(word.chars().count()).or_default().push(word);
+        }
+        for words in by_length.values_mut() {
+            words.sort_unstable();
+        }
+        by_length
+    })
+}
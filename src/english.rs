@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use spellcheck::Speller;
+
+/// Returns common English words, most frequent first.
+///
+/// This is the background "real text" vocabulary the detectors compare filler against.
+/// It is deliberately small; train a [`Speller`] on your own corpus for better coverage.
+pub fn common_words() -> &'static [&'static str] {
+    &[
+        "the", "of", "and", "to", "a", "in", "is", "it", "you", "that", "he", "was", "for", "on",
+        "are", "with", "as", "i", "his", "they", "be", "at", "one", "have", "this", "from", "or",
+        "had", "by", "not", "word", "but", "what", "some", "we", "can", "out", "other", "were",
+        "all", "there", "when", "up", "use", "your", "how", "said", "an", "each", "she", "which",
+        "do", "their", "time", "if", "will", "way", "about", "many", "then", "them", "write",
+        "would", "like", "so", "these", "her", "long", "make", "thing", "see", "him", "two",
+        "has", "look", "more", "day", "could", "go", "come", "did", "number", "sound", "no",
+        "most", "people", "my", "over", "know", "water", "than", "call", "first", "who", "may",
+        "down", "side", "been", "now", "find", "any", "new", "work", "part", "take", "get",
+        "place", "made", "live", "where", "after", "back", "little", "only", "round", "man",
+        "year", "came", "show", "every", "good", "me", "give", "our", "under", "name", "very",
+        "through", "just", "form", "sentence", "great", "think", "say", "help", "low", "line",
+        "differ", "turn", "cause", "much", "mean", "before", "move", "right", "boy", "old",
+        "too", "same", "tell", "does", "set", "three", "want", "air", "well", "also", "play",
+        "small", "end", "put", "home", "read", "hand", "port", "large", "spell", "add", "even",
+        "land", "here", "must", "big", "high", "such", "follow", "act", "why", "ask", "men",
+        "change", "went", "light", "kind", "off", "need", "house", "picture", "try", "us",
+        "again", "animal", "point", "mother", "world", "near", "build", "self", "earth",
+        "father", "head", "stand", "own", "page", "should", "country", "found", "answer",
+        "school", "grow", "study", "still", "learn", "plant", "cover", "food", "sun", "four",
+        "between", "state", "keep", "eye", "never", "last", "let", "thought", "city", "tree",
+        "cross", "farm", "hard", "start", "might", "story", "saw", "far", "sea", "draw", "left",
+        "late", "run", "while", "press", "close", "night", "real", "life", "few", "north",
+        "open", "seem", "together", "next", "white", "children", "begin", "got", "walk",
+        "example", "ease", "paper", "group", "always", "music", "those", "both", "mark",
+        "often", "letter", "until", "mile", "river", "car", "feet", "care", "second", "book",
+        "carry", "took", "science", "eat", "room", "friend", "began", "idea", "fish",
+        "mountain", "stop", "once", "base", "hear", "horse", "cut", "sure", "watch", "color",
+        "face", "wood", "main", "enough", "plain", "girl", "usual", "young", "ready", "above",
+        "ever", "red", "list", "though", "feel", "talk", "bird", "soon", "body", "dog",
+        "family", "direct", "pose", "leave", "song", "measure", "door", "product", "black",
+        "short", "numeral", "class", "wind", "question", "happen", "complete", "ship", "area",
+        "half", "rock", "order", "fire", "south", "problem", "piece", "told", "knew", "pass",
+        "since", "top", "whole", "king", "space", "heard", "best", "hour", "better", "true",
+        "during", "hundred", "five", "remember", "step", "early", "hold", "west", "ground",
+        "interest", "reach", "fast", "verb", "sing", "listen", "six", "table", "travel", "less",
+        "morning", "ten", "simple", "several", "vowel", "toward", "war", "lay", "against",
+        "pattern", "slow", "center", "love", "person", "money", "serve", "appear", "road",
+        "map", "rain", "rule", "govern", "pull", "cold", "notice", "voice", "unit", "power",
+        "town", "fine", "certain", "fly", "fall", "lead", "cry", "dark", "machine", "note",
+        "wait", "plan", "figure", "star", "box", "noun", "field", "rest", "correct", "able",
+        "pound", "done", "beauty", "drive", "stood", "contain", "front", "teach", "week",
+        "final", "gave", "green", "oh", "quick", "develop", "ocean", "warm", "free", "minute",
+        "strong", "special", "mind", "behind", "clear", "tail", "produce", "fact", "street",
+        "inch", "multiply", "nothing", "course", "stay", "wheel", "full", "force", "blue",
+        "object", "decide", "surface", "deep", "moon", "island", "foot", "system", "busy",
+        "test", "record", "boat", "common", "gold", "possible", "plane", "stead", "dry",
+        "wonder", "laugh", "thousand", "ago", "ran", "check", "game", "shape", "equate", "hot",
+        "miss", "brought", "heat", "snow", "tire", "bring", "yes", "distant", "fill", "east",
+        "paint", "language", "among", "user", "users", "file", "files", "data", "text",
+        "button", "click", "login", "log", "account", "email", "password", "settings",
+        "search", "menu", "save", "cancel", "submit", "delete", "edit", "update", "error",
+        "message", "please", "enter", "value", "required", "invalid", "welcome",
+        "sign", "profile", "view", "details", "loading", "version", "release", "install",
+        "code", "function", "module", "library", "package", "support",
+        "feature", "features", "issue", "issues", "document", "documentation", "project",
+        "using", "used", "following", "default", "option", "options",
+        "output", "input", "content", "description", "title", "image", "link", "contact",
+        "privacy", "policy", "terms", "service", "services", "customer", "customers",
+        "products", "price", "orders", "cart", "checkout", "shipping",
+        "company", "team", "news", "blog", "post", "posts", "comment", "comments",
+        "share", "download", "upload", "item", "items", "pages",
+        "started", "today", "trial", "plans",
+        "writing", "words", "written", "sentences", "paragraph",
+        "examples", "brown", "report", "reports", "bug", "bugs",
+        "fix", "fixed", "tests", "checks", "review", "design", "designs",
+        "mockup", "layout", "placeholder", "copy", "draft", "shipped", "string",
+        "strings", "interface", "screen", "window", "dialog", "label", "labels", "hello",
+        "thanks", "thank", "sorry", "yesterday", "tomorrow", "month", "months",
+        "years", "everyone", "someone", "something", "anything",
+        "everything", "because", "however", "therefore", "although", "without",
+        "within", "across", "around", "another", "whether", "either", "neither", "already",
+        "almost", "really", "actually", "probably", "maybe", "perhaps", "quite", "rather",
+        "especially", "important", "different", "available", "information",
+        "business", "government", "development", "experience", "research", "education",
+        "community", "health", "market", "process", "program", "public", "social", "local",
+        "national", "international", "general", "political", "economic", "human",
+        "include", "including", "provide", "provides", "provided", "create", "created",
+        "allow", "allows", "continue", "consider", "increase", "require", "requires",
+        "understand", "become", "became", "believe", "remain", "suggest", "expect",
+        "describe", "explain", "receive", "return", "returns", "happened", "seems",
+        "being", "having", "getting", "going", "doing", "making", "taking", "looking",
+        "working", "trying", "coming", "thinking", "saying", "seeing", "knowing", "telling",
+    ]
+}
+
+/// Returns identifiers and keywords common in source code, so words like "fn" and "impl" in a
+/// source file read as real text rather than as filler.
+pub fn programming_words() -> &'static [&'static str] {
+    &[
+        "fn", "mut", "pub", "impl", "struct", "enum", "trait", "mod", "crate", "const",
+        "static", "super", "else", "elif", "def", "var", "func", "int", "bool", "str",
+        "char", "float", "usize", "vec", "len", "args", "arg", "src", "std", "io", "fmt",
+        "println", "printf", "async", "await", "null", "nil", "none", "false",
+        "dyn", "ref", "ptr", "buf", "err", "ok", "todo", "config", "util", "utils",
+        "init", "tmp", "temp", "param", "params", "num", "idx", "json", "http", "url",
+        "api", "cli", "max", "min", "iter", "unwrap", "clone", "collect",
+        "filter", "assert", "eq", "ne", "cfg", "derive", "debug", "serde", "rng", "foo", "bar",
+    ]
+}
+
+/// Returns word frequencies for [`common_words`] followed by [`programming_words`], following
+/// Zipf's law: the word at rank `r` (1-based) gets a count of `100_000 / r`.
+pub fn word_frequencies() -> HashMap<&'static str, u32> {
+    let mut frequencies = HashMap::new();
+    for (rank, word) in common_words().iter().chain(programming_words()).enumerate() {
+        frequencies.insert(*word, 100_000 / (rank as u32 + 1));
+    }
+    frequencies
+}
+
+/// Returns a [`Speller`] whose dictionary is [`word_frequencies`].
+pub fn speller() -> Speller {
+    Speller {
+        letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+        n_words: word_frequencies()
+            .into_iter()
+            .map(|(word, count)| (word.to_string(), count))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_common_words_are_distinct() {
+        let mut seen = HashSet::new();
+        let words = common_words().iter().chain(programming_words());
+        let repeats: Vec<&str> = words.copied().filter(|word| !seen.insert(*word)).collect();
+        assert!(repeats.is_empty(), "repeated words: {:?}", repeats);
+        // Every word keeps its own rank, so the frequencies strictly follow the lists.
+        let frequencies = word_frequencies();
+        assert_eq!(frequencies.len(), common_words().len() + programming_words().len());
+        assert_eq!(frequencies["the"], 100_000);
+        assert_eq!(frequencies["of"], 50_000);
+    }
+}
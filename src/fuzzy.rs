@@ -0,0 +1,193 @@
+//! Recognizing misspelled filler words.
+//!
+//! Placeholder text typed by hand is often misspelled ("lorm", "ipsm", "consecteur"), and such
+//! words miss the exact corpus lookups of [`classify_word`](crate::ipsum_classifier::classify_word).
+//! [`closest_corpus_word`] finds the [`dolor::corpus`] word nearest to a word by
+//! Damerau-Levenshtein distance and reports how confident the match is.
+//!
+//! Short English words are often one edit away from a corpus word ("edit" and "elit", "else"
+//! and "esse", "quit" and "quis"), so the matching is deliberately strict for them: words
+//! shorter than [`FuzzyOptions::min_len`] never match, and words shorter than
+//! [`FuzzyOptions::strict_below`] only match a corpus word they could be a typo of by a dropped
+//! letter or two swapped neighbours. Words the dictionary knows are never matched by
+//! [`fuzzy_match`].
+
+use spellcheck::Speller;
+
+use crate::dolor;
+
+/// Controls how far a word may be from a corpus word and still match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzyOptions {
+    /// The largest edit distance that still matches. Longer words are allowed one edit per
+    /// four letters, up to this limit.
+    pub max_distance: usize,
+    /// Words with fewer letters than this never match.
+    pub min_len: usize,
+    /// Words with fewer letters than this only match by one dropped letter or one swap of
+    /// neighbouring letters, not by a wrong or extra letter.
+    pub strict_below: usize,
+}
+
+impl Default for FuzzyOptions {
+    /// At most two edits, for words of four letters or more, strict below six letters.
+    fn default() -> Self {
+        FuzzyOptions { max_distance: 2, min_len: 4, strict_below: 6 }
+    }
+}
+
+impl FuzzyOptions {
+    /// How many edits a word of `len` letters may be away from a corpus word.
+    pub fn allowed_distance(&self, len: usize) -> usize {
+        if len < self.min_len {
+            return 0;
+        }
+        (len / 4).max(1).min(self.max_distance)
+    }
+}
+
+/// A corpus word that a misspelled word is close to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyMatch {
+    /// The corpus word.
+    pub lemma: &'static str,
+    /// The Damerau-Levenshtein distance between the word and the corpus word.
+    pub distance: usize,
+    /// How sure the match is, in `[0, 1]`: one minus the share of the longer word's letters
+    /// that had to be edited. An exact match has confidence 1.
+    pub confidence: f64,
+}
+
+/// The Damerau-Levenshtein distance between `a` and `b`, in characters: the fewest insertions,
+/// deletions, substitutions and swaps of adjacent characters that turn one into the other,
+/// where no character is edited twice (the optimal string alignment distance).
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Three rows of the dynamic programming table: two back, one back and the current one.
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+/// Whether `word` is `lemma` with one letter dropped or two neighbouring letters swapped.
+fn is_typing_slip(word: &[char], lemma: &[char]) -> bool {
+    if word.len() + 1 == lemma.len() {
+        return (0..lemma.len()).any(|skip| {
+            lemma.iter().enumerate().filter(|&(i, _)| i != skip).map(|(_, c)| c).eq(word.iter())
+        });
+    }
+    if word.len() == lemma.len() {
+        let differing: Vec<usize> = (0..word.len()).filter(|&i| word[i] != lemma[i]).collect();
+        return matches!(differing[..], [i, j] if j == i + 1 && word[i] == lemma[j] && word[j] == lemma[i]);
+    }
+    false
+}
+
+/// The corpus word closest to `word`, ignoring case, if it is within the distance
+/// [`FuzzyOptions`] allow for a word of its length. Ties go to the alphabetically first word.
+pub fn closest_corpus_word(word: &str, options: &FuzzyOptions) -> Option<FuzzyMatch> {
+    closest_corpus_word_with_case(&word.to_lowercase(), options)
+}
+
+/// [`closest_corpus_word`] without lowercasing `word` first, for case-sensitive matching.
+/// The corpus is lowercase, so each capital counts as an edit.
+pub(crate) fn closest_corpus_word_with_case(word: &str, options: &FuzzyOptions) -> Option<FuzzyMatch> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.is_empty() || !chars.iter().all(|c| c.is_alphabetic()) {
+        return None;
+    }
+    let allowed = options.allowed_distance(chars.len());
+    let strict = chars.len() < options.strict_below;
+    let mut best: Option<FuzzyMatch> = None;
+    for (&len, lemmas) in dolor::corpus_by_length() {
+        if len.abs_diff(chars.len()) > allowed {
+            continue;
+        }
+        for &lemma in lemmas {
+            let distance = damerau_levenshtein(word, lemma);
+            let lemma_chars: Vec<char> = lemma.chars().collect();
+            let close = distance == 0
+                || (distance <= allowed && (!strict || (distance == 1 && is_typing_slip(&chars, &lemma_chars))));
+            if !close || best.is_some_and(|b| (b.distance, b.lemma) <= (distance, lemma)) {
+                continue;
+            }
+            let longest = chars.len().max(len) as f64;
+            best = Some(FuzzyMatch { lemma, distance, confidence: 1.0 - distance as f64 / longest });
+        }
+    }
+    best
+}
+
+/// [`closest_corpus_word`] for words the speller does not know; a word in the dictionary is
+/// real, however close it is to a corpus word.
+pub fn fuzzy_match(speller: &Speller, word: &str, options: &FuzzyOptions) -> Option<FuzzyMatch> {
+    let found = closest_corpus_word(word, options)?;
+    if found.distance > 0 && speller.n_words.contains_key(&word.to_lowercase()) {
+        return None;
+    }
+    Some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::english;
+
+    #[test]
+    fn test_damerau_levenshtein() {
+        assert_eq!(damerau_levenshtein("lorem", "lorem"), 0);
+        assert_eq!(damerau_levenshtein("lorm", "lorem"), 1);
+        assert_eq!(damerau_levenshtein("lroem", "lorem"), 1);
+        assert_eq!(damerau_levenshtein("consecteur", "consectetur"), 1);
+        assert_eq!(damerau_levenshtein("kitten", "sitting"), 3);
+        assert_eq!(damerau_levenshtein("ca", "abc"), 3);
+        assert_eq!(damerau_levenshtein("", "abc"), 3);
+        assert_eq!(damerau_levenshtein("straße", "strasse"), 2);
+    }
+
+    #[test]
+    fn test_matches_misspelled_filler() {
+        let options = FuzzyOptions::default();
+        for (typo, lemma) in [("lorm", "lorem"), ("ipsm", "ipsum"), ("dolr", "dolor"), ("consecteur", "consectetur"), ("Lroem", "lorem"), ("adipisicng", "adipiscing"), ("reprehendrit", "reprehenderit")] {
+            let found = closest_corpus_word(typo, &options).unwrap_or_else(|| panic!("{}", typo));
+            assert_eq!(found.lemma, lemma);
+            assert!(found.confidence > 0.7 && found.confidence < 1.0, "{:?}", found);
+        }
+        let exact = closest_corpus_word("Dolor", &options).unwrap();
+        assert_eq!((exact.distance, exact.confidence), (0, 1.0));
+        // Two swaps are within the default limit, but not within a limit of one.
+        assert_eq!(closest_corpus_word("cnosectteur", &options).map(|m| m.distance), Some(2));
+        assert!(closest_corpus_word("cnosectteur", &FuzzyOptions { max_distance: 1, ..options }).is_none());
+        assert!(closest_corpus_word("lorm", &FuzzyOptions { max_distance: 0, ..options }).is_none());
+    }
+
+    #[test]
+    fn test_short_english_words_are_not_flagged() {
+        let options = FuzzyOptions::default();
+        let english_words = [
+            "edit", "exit", "else", "quit", "quiz", "auto", "mint", "hint", "enum", "meet", "sat", "son", "add", "it", "is",
+            "dollar", "anime", "mauve", "metro", "minimum", "velvet", "tempest", "animal", "nullable", "culture", "curable",
+        ];
+        for word in english_words {
+            assert_eq!(closest_corpus_word(word, &options), None, "{}", word);
+        }
+        // Dropped-letter slips that are also English are left to the dictionary.
+        let mut speller = english::speller();
+        assert!(fuzzy_match(&speller, "tempo", &options).is_some());
+        speller.n_words.insert("tempo".to_string(), 1);
+        assert_eq!(fuzzy_match(&speller, "Tempo", &options), None);
+        assert!(fuzzy_match(&speller, "lorem", &options).is_some());
+    }
+}
@@ -0,0 +1,158 @@
+//! Greeking: hiding real copy behind filler of the same shape.
+//!
+//! [`greek_with_rng`] replaces every run of letters in a text with a word from the
+//! [`dolor::corpus`] of the same length, in the same case. Numbers, punctuation, whitespace and
+//! line breaks are kept byte for byte, so a mockup keeps its layout while its content is hidden.
+
+use std::ops::Range;
+
+use rand::Rng;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::dolor;
+use crate::tokenizer::{self, TokenKind};
+
+/// Whether a grapheme cluster starts with a letter.
+fn is_letter(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphabetic)
+}
+
+/// Whether a grapheme cluster starts with an uppercase letter.
+fn is_upper(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// The byte ranges of the runs of letters inside the words of `text`. Apostrophes, hyphens and
+/// digits inside a word separate its runs: "don't" has the runs "don" and "t".
+pub(crate) fn letter_runs(text: &str) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    for token in tokenizer::tokenize(text).into_iter().filter(|t| t.kind == TokenKind::Word) {
+        let mut start = None;
+        for (offset, grapheme) in token.text.grapheme_indices(true) {
+            let at = token.bytes.start + offset;
+            match (is_letter(grapheme), start) {
+                (true, None) => start = Some(at),
+                (false, Some(from)) => {
+                    runs.push(from..at);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(from) = start {
+            runs.push(from..token.bytes.end);
+        }
+    }
+    runs
+}
+
+/// Gives `word` the capitalization of `pattern`: all caps if `pattern` is a word of two or more
+/// letters in all caps, otherwise letter by letter, with letters past the end of `pattern`
+/// in lowercase.
+pub(crate) fn match_case(pattern: &str, word: &str) -> String {
+    let cases: Vec<bool> = pattern.graphemes(true).map(is_upper).collect();
+    if cases.len() > 1 && cases.iter().all(|&upper| upper) {
+        return word.to_uppercase();
+    }
+    word.graphemes(true)
+        .enumerate()
+        .map(|(i, grapheme)| match cases.get(i) {
+            Some(true) => grapheme.to_uppercase(),
+            _ => grapheme.to_lowercase(),
+        })
+        .collect()
+}
+
+/// A filler word of exactly `len` characters, drawn from the corpus. Lengths the corpus lacks
+/// are made by cutting a longer word short or, past its longest word, by joining words.
+fn filler_of_len<R: Rng + ?Sized>(len: usize, rng: &mut R) -> String {
+    let by_length = dolor::corpus_by_length();
+    let pick = |words: &[&'static str], rng: &mut R| words[rng.random_range(..words.len())];
+    if let Some(words) = by_length.get(&len) {
+        return pick(words, rng).to_string();
+    }
+    if let Some((_, words)) = by_length.range(len..).next() {
+        return pick(words, rng).chars().take(len).collect();
+    }
+    match by_length.last_key_value() {
+        Some((&longest, _)) if len > 0 => {
+            let head = (len / 2).clamp(1, longest);
+            filler_of_len(head, rng) + &filler_of_len(len - head, rng)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Replaces every word of `text` with filler of the same length and capitalization, keeping
+/// numbers, punctuation and whitespace exactly where they were.
+pub fn greek_with_rng<R: Rng + ?Sized>(text: &str, rng: &mut R) -> String {
+    let mut greeked = String::with_capacity(text.len());
+    let mut position = 0;
+    for run in letter_runs(text) {
+        let original = &text[run.clone()];
+        greeked.push_str(&text[position..run.start]);
+        greeked.push_str(&match_case(original, &filler_of_len(original.graphemes(true).count(), rng)));
+        position = run.end;
+    }
+    greeked.push_str(&text[position..]);
+    greeked
+}
+
+/// Greeks `text` using the thread-local RNG; see [`greek_with_rng`].
+pub fn greek(text: &str) -> String {
+    greek_with_rng(text, &mut rand::rng())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lorem::seeded_rng;
+
+    /// Replaces every letter with `x` or `X`, leaving the shape of the text.
+    fn shape(text: &str) -> String {
+        text.graphemes(true)
+            .map(|g| match (is_letter(g), is_upper(g)) {
+                (true, true) => "X",
+                (true, false) => "x",
+                (false, _) => g,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_greeking_keeps_the_layout() {
+        let text = "Q3 Revenue Report\n\n  - Sales rose 12.5% to $4,200 (see TABLE 2).\n\tDon't share: well-known partners, e.g. McDonald's.\n";
+        let greeked = greek_with_rng(text, &mut seeded_rng(5));
+        assert_ne!(greeked, text);
+        assert_eq!(shape(&greeked), shape(text));
+        assert_eq!(greeked.lines().count(), text.lines().count());
+        assert!(greeked.contains(" 12.5% ") && greeked.contains("$4,200") && greeked.contains(" 2)."));
+        for word in ["Revenue", "Report", "Sales", "share", "partners"] {
+            assert!(!greeked.contains(word), "{}", greeked);
+        }
+        let corpus = dolor::corpus();
+        for run in letter_runs(&greeked) {
+            assert!(corpus.contains(greeked[run].to_lowercase().as_str()));
+        }
+        assert_eq!(greeked, greek_with_rng(text, &mut seeded_rng(5)));
+    }
+
+    #[test]
+    fn test_lengths_outside_the_corpus() {
+        let long = "Pneumonoultramicroscopicsilicovolcanoconiosis";
+        let greeked = greek_with_rng(long, &mut seeded_rng(1));
+        assert_eq!(greeked.chars().count(), long.chars().count());
+        assert_eq!(shape(&greeked), shape(long));
+        // A letter with a combining accent counts as one character.
+        assert_eq!(greek_with_rng("cafe\u{301}", &mut seeded_rng(1)).graphemes(true).count(), 4);
+        assert_eq!(greek(""), "");
+    }
+
+    #[test]
+    fn test_match_case() {
+        assert_eq!(match_case("NASA", "ipsum"), "IPSUM");
+        assert_eq!(match_case("McDonald", "consecte"), "CoNsecte");
+        assert_eq!(match_case("I", "a"), "A");
+        assert_eq!(match_case("Hi", "sed"), "Sed");
+    }
+}
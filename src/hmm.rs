@@ -0,0 +1,377 @@
+//! Segmenting text into real and filler regions with a hidden Markov model.
+//!
+//! Per-word statuses flicker: a Latin-looking English word is flagged in the middle of a
+//! paragraph, and "in" or "et" inside filler reads as English. A [`FillerHmm`] treats the
+//! words of a text as the output of a two-state [`State::Real`]/[`State::Filler`] Markov
+//! chain that tends to stay where it is, so a word is judged together with its neighbours.
+//!
+//! The emissions come from the class models of a [`LoremDetector`]: the lorem model (the
+//! words of a [`MarkovModel`](crate::model::MarkovModel) plus [`dolor::corpus`](crate::dolor::corpus))
+//! and the English model ([`english::common_words`](crate::english::common_words)), with words
+//! the caller's dictionary knows leaning English as in [`LoremDetector::detect_spans`]. Only the
+//! ratio of the two emission probabilities matters to the decoding, so a word emits
+//! `exp(llr)` from the filler state and 1 from the real state, where `llr` is the word's
+//! log-likelihood ratio. Viterbi finds the most likely state sequence and forward-backward
+//! gives each word's posterior probability of being filler.
+//!
+//! The start and transition probabilities are [`HmmParams`], which
+//! [`HmmParams::estimate`] learns from labeled examples.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::detector::{normalize_word, LoremDetector};
+use crate::matcher::WordMatcher;
+use crate::tokenizer;
+
+/// A hidden state of the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum State {
+    /// Text a person wrote.
+    Real,
+    /// Placeholder text.
+    Filler,
+}
+
+impl State {
+    const ALL: [State; 2] = [State::Real, State::Filler];
+
+    fn index(self) -> usize {
+        match self {
+            State::Real => 0,
+            State::Filler => 1,
+        }
+    }
+}
+
+/// The smallest probability a [`FillerHmm`] uses, so no path is ruled out entirely.
+const MIN_PROBABILITY: f64 = 1e-9;
+
+/// The start and transition probabilities of a [`FillerHmm`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HmmParams {
+    /// The probability that a text starts in filler.
+    pub start_filler: f64,
+    /// The probability that the word after a real word is filler.
+    pub real_to_filler: f64,
+    /// The probability that the word after a filler word is real.
+    pub filler_to_real: f64,
+}
+
+impl Default for HmmParams {
+    /// Either state is equally likely at the start, and one word in ten switches state.
+    fn default() -> Self {
+        HmmParams { start_filler: 0.5, real_to_filler: 0.1, filler_to_real: 0.1 }
+    }
+}
+
+impl HmmParams {
+    /// Estimates the parameters from labeled examples, each a sequence of pieces of text
+    /// with the state they were written in.
+    ///
+    /// Every word of an example is labeled with the state of its piece, and the
+    /// probabilities are the observed frequencies of first states and state changes with
+    /// add-one smoothing, so no probability is ever 0 or 1. Without examples this is
+    /// [`HmmParams::default`] up to the smoothing: one half everywhere.
+    pub fn estimate(examples: &[&[(&str, State)]]) -> Self {
+        let mut starts = [0u32; 2];
+        let mut transitions = [[0u32; 2]; 2];
+        for example in examples {
+            let labels: Vec<State> = example
+                .iter()
+                .flat_map(|&(text, state)| {
+                    let words = tokenizer::words(text).filter(|word| normalize_word(word.text).is_some()).count();
+                    std::iter::repeat_n(state, words)
+                })
+                .collect();
+            if let Some(first) = labels.first() {
+                starts[first.index()] += 1;
+            }
+            for pair in labels.windows(2) {
+                transitions[pair[0].index()][pair[1].index()] += 1;
+            }
+        }
+        let smoothed = |hits: u32, total: u32| (f64::from(hits) + 1.0) / (f64::from(total) + 2.0);
+        HmmParams {
+            start_filler: smoothed(starts[1], starts[0] + starts[1]),
+            real_to_filler: smoothed(transitions[0][1], transitions[0][0] + transitions[0][1]),
+            filler_to_real: smoothed(transitions[1][0], transitions[1][0] + transitions[1][1]),
+        }
+    }
+
+    /// The parameters moved into `[MIN_PROBABILITY, 1 - MIN_PROBABILITY]`, with NaN taken as
+    /// one half.
+    fn clamped(self) -> Self {
+        let clamp = |p: f64| if p.is_nan() { 0.5 } else { p.clamp(MIN_PROBABILITY, 1.0 - MIN_PROBABILITY) };
+        HmmParams {
+            start_filler: clamp(self.start_filler),
+            real_to_filler: clamp(self.real_to_filler),
+            filler_to_real: clamp(self.filler_to_real),
+        }
+    }
+
+    /// Natural logs of the start probabilities, indexed by [`State`].
+    fn log_start(&self) -> [f64; 2] {
+        [(1.0 - self.start_filler).ln(), self.start_filler.ln()]
+    }
+
+    /// Natural logs of the transition probabilities, indexed by from and to [`State`].
+    fn log_transitions(&self) -> [[f64; 2]; 2] {
+        [
+            [(1.0 - self.real_to_filler).ln(), self.real_to_filler.ln()],
+            [self.filler_to_real.ln(), (1.0 - self.filler_to_real).ln()],
+        ]
+    }
+}
+
+/// A word of a decoded text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedWord {
+    /// Byte range of the word in the text.
+    pub range: Range<usize>,
+    /// The word's state on the most likely path.
+    pub state: State,
+    /// The posterior probability that the word is filler, given the whole text.
+    pub filler_probability: f64,
+}
+
+/// A run of words that the most likely path spends in [`State::Filler`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HmmSegment {
+    /// Byte range of the run, from the start of its first word to the end of its last.
+    pub range: Range<usize>,
+    /// The mean posterior probability that the run's words are filler.
+    pub probability: f64,
+    /// The number of words in the run.
+    pub words: usize,
+}
+
+/// A two-state hidden Markov model over the words of a text.
+#[derive(Debug, Clone)]
+pub struct FillerHmm {
+    detector: LoremDetector,
+    params: HmmParams,
+}
+
+impl Default for FillerHmm {
+    /// A model over the default [`LoremDetector`] with the default parameters.
+    fn default() -> Self {
+        FillerHmm::new(LoremDetector::default())
+    }
+}
+
+impl FillerHmm {
+    /// Creates a model whose emissions come from the class models of `detector`.
+    pub fn new(detector: LoremDetector) -> Self {
+        FillerHmm { detector, params: HmmParams::default() }
+    }
+
+    /// Sets the start and transition probabilities. Probabilities of 0 or 1, or outside that
+    /// range, are moved just inside it, so every state sequence stays possible.
+    pub fn with_params(mut self, params: HmmParams) -> Self {
+        self.params = params.clamped();
+        self
+    }
+
+    /// The start and transition probabilities.
+    pub fn params(&self) -> &HmmParams {
+        &self.params
+    }
+
+    /// Decodes the words of `text`, as [`scan_text`](crate::ipsum::scan_text) finds them over
+    /// `dictionary`. Words without letters are not observations and are left out.
+    pub fn decode(&self, dictionary: &WordMatcher, text: &str) -> Vec<DecodedWord> {
+        let words = self.detector.scored_words(dictionary, text);
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let emissions: Vec<[f64; 2]> = words.iter().map(|&(_, ratio)| [0.0, ratio]).collect();
+        let start = self.params.log_start();
+        let transitions = self.params.log_transitions();
+        let path = viterbi(&emissions, start, transitions);
+        let posteriors = posteriors(&emissions, start, transitions);
+        words
+            .into_iter()
+            .zip(path)
+            .zip(posteriors)
+            .map(|(((range, _), state), filler_probability)| DecodedWord { range, state, filler_probability })
+            .collect()
+    }
+
+    /// The runs of filler on the most likely path through `text`, with how sure the model
+    /// is of each.
+    pub fn filler_segments(&self, dictionary: &WordMatcher, text: &str) -> Vec<HmmSegment> {
+        let decoded = self.decode(dictionary, text);
+        let mut segments = Vec::new();
+        for run in decoded.chunk_by(|a, b| a.state == b.state) {
+            if run[0].state != State::Filler {
+                continue;
+            }
+            let total: f64 = run.iter().map(|word| word.filler_probability).sum();
+            segments.push(HmmSegment {
+                range: run[0].range.start..run[run.len() - 1].range.end,
+                probability: total / run.len() as f64,
+                words: run.len(),
+            });
+        }
+        segments
+    }
+}
+
+/// The most likely state sequence given per-word emission log-probabilities.
+fn viterbi(emissions: &[[f64; 2]], start: [f64; 2], transitions: [[f64; 2]; 2]) -> Vec<State> {
+    let mut scores = [start[0] + emissions[0][0], start[1] + emissions[0][1]];
+    // back[t][s] is the best state before state s at word t + 1.
+    let mut back: Vec<[State; 2]> = Vec::with_capacity(emissions.len());
+    for emission in &emissions[1..] {
+        let mut next = [0.0; 2];
+        let mut from = [State::Real; 2];
+        for to in State::ALL {
+            let (best, score) = State::ALL
+                .into_iter()
+                .map(|s| (s, scores[s.index()] + transitions[s.index()][to.index()]))
+                .fold((State::Real, f64::NEG_INFINITY), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+            next[to.index()] = score + emission[to.index()];
+            from[to.index()] = best;
+        }
+        scores = next;
+        back.push(from);
+    }
+    let mut state = if scores[1] > scores[0] { State::Filler } else { State::Real };
+    let mut path = vec![state];
+    for from in back.iter().rev() {
+        state = from[state.index()];
+        path.push(state);
+    }
+    path.reverse();
+    path
+}
+
+/// The posterior probability of [`State::Filler`] at every word, by forward-backward in log space.
+fn posteriors(emissions: &[[f64; 2]], start: [f64; 2], transitions: [[f64; 2]; 2]) -> Vec<f64> {
+    let n = emissions.len();
+    let mut forward = vec![[0.0; 2]; n];
+    forward[0] = [start[0] + emissions[0][0], start[1] + emissions[0][1]];
+    for t in 1..n {
+        for to in 0..2 {
+            let incoming = log_sum_exp(forward[t - 1][0] + transitions[0][to], forward[t - 1][1] + transitions[1][to]);
+            forward[t][to] = incoming + emissions[t][to];
+        }
+    }
+    let mut backward = vec![[0.0; 2]; n];
+    for t in (0..n - 1).rev() {
+        for from in 0..2 {
+            backward[t][from] = log_sum_exp(
+                transitions[from][0] + emissions[t + 1][0] + backward[t + 1][0],
+                transitions[from][1] + emissions[t + 1][1] + backward[t + 1][1],
+            );
+        }
+    }
+    (0..n)
+        .map(|t| {
+            let real = forward[t][0] + backward[t][0];
+            let filler = forward[t][1] + backward[t][1];
+            (filler - log_sum_exp(real, filler)).exp()
+        })
+        .collect()
+}
+
+fn log_sum_exp(a: f64, b: f64) -> f64 {
+    let max = a.max(b);
+    if max == f64::NEG_INFINITY {
+        return f64::NEG_INFINITY;
+    }
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::english;
+
+    const LOREM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+
+    #[test]
+    fn test_finds_the_filler_between_real_text() {
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let text = format!("Please read the page before you start. {} Tell me what you think of the work.", LOREM);
+        let hmm = FillerHmm::default();
+        let segments = hmm.filler_segments(&dictionary, &text);
+        let start = text.find("Lorem").unwrap();
+        assert_eq!(segments.len(), 1, "{:?}", segments);
+        assert_eq!(segments[0].range, start..start + LOREM.len() - 1);
+        assert_eq!(segments[0].words, 19);
+        assert!(segments[0].probability > 0.9, "{:?}", segments[0]);
+
+        let decoded = hmm.decode(&dictionary, &text);
+        assert!(decoded.iter().all(|word| (0.0..=1.0).contains(&word.filler_probability)));
+        // Shared words such as "sit", "do" and "et" go with the filler around them.
+        for word in decoded.iter().filter(|word| segments[0].range.contains(&word.range.start)) {
+            assert_eq!(word.state, State::Filler, "{}", &text[word.range.clone()]);
+        }
+        for word in decoded.iter().filter(|word| !segments[0].range.contains(&word.range.start)) {
+            assert!(word.filler_probability < 0.1, "{}", &text[word.range.clone()]);
+        }
+        assert!(hmm.filler_segments(&dictionary, "Tell me what you think.").is_empty());
+        assert!(hmm.decode(&dictionary, " -- 42 ").is_empty());
+    }
+
+    #[test]
+    fn test_transitions_smooth_single_words() {
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let text = "The quorum met in the atrium for the agenda.";
+        // With no memory, Latin-looking English words are filler on their own.
+        let memoryless = FillerHmm::default().with_params(HmmParams { start_filler: 0.5, real_to_filler: 0.5, filler_to_real: 0.5 });
+        let flagged: Vec<&str> = memoryless
+            .filler_segments(&dictionary, text)
+            .into_iter()
+            .map(|segment| &text[segment.range])
+            .collect();
+        assert!(flagged.contains(&"atrium"), "{:?}", flagged);
+        // A sticky chain outvotes them with their English neighbours.
+        let sticky = FillerHmm::default().with_params(HmmParams { start_filler: 0.01, real_to_filler: 0.001, filler_to_real: 0.5 });
+        assert!(sticky.filler_segments(&dictionary, text).is_empty());
+        let decoded = sticky.decode(&dictionary, LOREM);
+        assert!(decoded.iter().all(|word| word.state == State::Filler));
+    }
+
+    #[test]
+    fn test_estimate_from_labeled_examples() {
+        let first: &[(&str, State)] = &[("one two three", State::Real), ("lorem ipsum", State::Filler)];
+        let second: &[(&str, State)] = &[("dolor sit amet", State::Filler), ("four", State::Real), ("5 ,", State::Filler)];
+        let params = HmmParams::estimate(&[first, second]);
+        // Starts: one real, one filler. Real words: two stay real, one turns to filler.
+        // Filler words: three stay filler, one turns real; the trailing "5 ," has no words.
+        assert_eq!(params.start_filler, 2.0 / 4.0);
+        assert_eq!(params.real_to_filler, 2.0 / 5.0);
+        assert_eq!(params.filler_to_real, 2.0 / 6.0);
+        assert_eq!(HmmParams::estimate(&[]), HmmParams { start_filler: 0.5, real_to_filler: 0.5, filler_to_real: 0.5 });
+
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(serde_json::from_str::<HmmParams>(&json).unwrap(), params);
+        assert_eq!(*FillerHmm::default().with_params(params).params(), params);
+    }
+
+    #[test]
+    fn test_extreme_params_are_clamped() {
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let text = format!("Please read the page. {}", LOREM);
+        for params in [
+            HmmParams { start_filler: 0.0, real_to_filler: 0.0, filler_to_real: 0.5 },
+            HmmParams { start_filler: 1.0, real_to_filler: 1.0, filler_to_real: 1.0 },
+            HmmParams { start_filler: -3.0, real_to_filler: f64::NAN, filler_to_real: 7.0 },
+        ] {
+            let hmm = FillerHmm::default().with_params(params);
+            let clamped = hmm.params();
+            for p in [clamped.start_filler, clamped.real_to_filler, clamped.filler_to_real] {
+                assert!(p > 0.0 && p < 1.0, "{:?}", clamped);
+            }
+            for word in hmm.decode(&dictionary, &text) {
+                assert!((0.0..=1.0).contains(&word.filler_probability), "{:?}: {:?}", params, word);
+            }
+        }
+        assert_eq!(log_sum_exp(f64::NEG_INFINITY, f64::NEG_INFINITY), f64::NEG_INFINITY);
+        assert_eq!(log_sum_exp(f64::NEG_INFINITY, 0.0), 0.0);
+    }
+}
@@ -1,161 +1,513 @@
-use std::ops::Range;
+use std::ops::{Range, RangeInclusive};
 
-use crate::ipsum_classifier::classify_word;
 // Assuming Speller is defined in a module named `speller` within the crate
 use spellcheck::Speller;
 
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::classifier::Classifier;
+use crate::config::ClassifierConfig;
+use crate::ipsum_classifier::{Explanation, Rule};
+use crate::matcher::{longest_known_substring, WordMatcher};
+use crate::model::{GenerateError, MarkovModel};
+use crate::structure::{self, TextShape, TextUnit};
+use crate::tokenizer::{self, Token, TokenKind};
+
 
 /// Represents the classification result of a word.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IpsumStatus {
     /// Not Ipsum or Lorem
     NotLorem,
     /// No valid substring was found in the word the word appears to be pure ipsum.
     FullyIpsum,
     /// A valid substring was found inside the word.
-    /// Contains the valid substring and its position (start..end) within the word.
+    /// Contains the valid substring and its byte range (start..end) within the original word,
+    /// widened if needed so it never splits a character or grapheme cluster.
     Partial { valid: String, range: Range<usize> },
 }
 
-// /// A simple classifier function that uses the dolar corpus to decide if a word is "Ipsum" (i.e. filler) or not.
-// /// If the entire word is found in the corpus, it is classified as FullyIpsum.  
-// /// Otherwise, if a substring from the corpus covers at least 70% of the word, it's Partial;
-// /// if no substring is found, the word is NotLorem.
-// pub fn classify_word(word: &str, corpus: &HashSet<&'static str>) -> IpsumStatus {
-//     let lower = word.to_lowercase();
-
-//     // If the word is entirely in the corpus, it's fake.
-//     if corpus.contains(lower.as_str()) {
-//         return IpsumStatus::FullyIpsum;
-//     }
-
-//     // Otherwise, search for valid substrings from the corpus.
-//     let mut best: Option<(String, Range<usize>)> = None;
-//     for i in 0..lower.len() {
-//         for j in (i + 1)..=lower.len() {
-//             let sub = &lower[i..j];
-//             if corpus.contains(sub) {
-//                 best = match best {
-//                     Some((ref best_str, ref best_range)) => {
-//                         if sub.len() > best_str.len() {
-//                             Some((sub.to_string(), i..j))
-//                         } else {
-//                             Some((best_str.clone(), best_range.clone()))
-//                         }
-//                     }
-//                     None => Some((sub.to_string(), i..j)),
-//                 };
-//             }
-//         }
-//     }
-
-//     if let Some((valid, range)) = best {
-//         let ratio = valid.len() as f64 / lower.len() as f64;
-//         if ratio >= 0.7 {
-//             IpsumStatus::Partial { valid, range }
-//         } else {
-//             IpsumStatus::FullyIpsum
-//         }
-//     } else {
-//         IpsumStatus::NotLorem
-//     }
-// }
-
-// /// Given a reference to a trained speller and a word, classify it as either Correct,
-// /// FullyIpsum, or Partial if it contains a valid substring.
-// /// 
-// /// The spellers dictionary is assumed to be stored in lowercase.
-// /// (This function performs case-insensitive matching.)
+impl IpsumStatus {
+    /// Whether the word is filler, fully or in part.
+    pub fn is_filler(&self) -> bool {
+        *self != IpsumStatus::NotLorem
+    }
+}
+
+/// Classifies a word against the dictionary of `speller` alone: a word the dictionary knows
+/// is [`IpsumStatus::NotLorem`], one that contains a dictionary word is
+/// [`IpsumStatus::Partial`] with the longest such word, and anything else is
+/// [`IpsumStatus::FullyIpsum`].
+///
+/// The speller's dictionary is assumed to be stored in lowercase; matching ignores case.
 pub fn classify_word_with_speller(speller: &Speller, word: &str) -> IpsumStatus {
-    let lower_word = word.to_lowercase();
-
-    // If the whole word is found, it's correct.
-    if speller.n_words.contains_key(&lower_word) {
-        return IpsumStatus::NotLorem;
-    }
-
-    // Otherwise, search for valid substrings.
-    // We'll try every possible substring and pick the longest valid one.
-    let mut best: Option<(String, Range<usize>)> = None;
-    for i in 0..lower_word.len() {
-        for j in (i + 1)..=lower_word.len() {
-            let sub = &lower_word[i..j];
-            if speller.n_words.contains_key(sub) {
-                let candidate = (sub.to_string(), i..j);
-                best = match best {
-                    Some((ref best_str, ref best_range)) => {
-                        if candidate.0.len() > best_str.len() {
-                            Some(candidate)
-                        } else {
-                            Some((best_str.clone(), best_range.clone()))
-                        }
-                    }
-                    None => Some(candidate),
-                };
+    explain_word_with_speller(speller, word).status
+}
+
+/// [`classify_word_with_speller`], along with the rule that decided it.
+pub(crate) fn explain_word_with_speller(speller: &Speller, word: &str) -> Explanation {
+    let config = ClassifierConfig::default();
+    let lower = LoweredWord::new(word);
+    let known = is_known_word(lower.as_str(), |w| speller.n_words.contains_key(w));
+    dictionary_explanation(&lower, known, || longest_known_substring(&speller.n_words, lower.as_str()), &config)
+}
+
+/// [`classify_word_with_speller`] against a dictionary that was compiled once with
+/// [`WordMatcher::from_speller`], which finds the longest valid substring in linear time.
+pub fn classify_word_with_matcher(dictionary: &WordMatcher, word: &str) -> IpsumStatus {
+    classify_word_with_matcher_and_config(dictionary, word, &ClassifierConfig::default())
+}
+
+/// [`classify_word_with_matcher`], with the word lists, case handling and minimum lengths
+/// of `config`.
+pub fn classify_word_with_matcher_and_config(dictionary: &WordMatcher, word: &str, config: &ClassifierConfig) -> IpsumStatus {
+    explain_word_with_matcher_and_config(dictionary, word, config).status
+}
+
+/// [`classify_word_with_matcher_and_config`], along with the rule that decided it.
+pub(crate) fn explain_word_with_matcher_and_config(dictionary: &WordMatcher, word: &str, config: &ClassifierConfig) -> Explanation {
+    let lower = LoweredWord::with_case(word, config.case_sensitive);
+    let known = is_known_word(lower.as_str(), |w| dictionary.contains(w));
+    dictionary_explanation(&lower, known, || dictionary.longest_match(lower.as_str()), config)
+}
+
+/// Whether `is_word` accepts `word`, or the stem of the English contraction it is: "don't" is
+/// known if "do" is, and "can't" if "can" is.
+pub(crate) fn is_known_word(word: &str, is_word: impl Fn(&str) -> bool) -> bool {
+    is_word(word)
+        || tokenizer::split_clitic(word).is_some_and(|(stem, clitic)| {
+            // "can't" splits as "ca" and "n't"; keep the "n" for stems like it.
+            is_word(stem) || (clitic.starts_with(['n', 'N']) && is_word(&word[..stem.len() + 1]))
+        })
+}
+
+/// The dictionary rules, given whether the dictionary knows the whole word and a way to find
+/// the longest dictionary word inside it.
+fn dictionary_explanation(
+    word: &LoweredWord,
+    known: bool,
+    longest: impl FnOnce() -> Option<Range<usize>>,
+    config: &ClassifierConfig,
+) -> Explanation {
+    let explain = |status, rule| Explanation::new(status, rule, config);
+    // Ignored words and words that are too short are real.
+    if config.is_ignored(word.original()) {
+        return explain(IpsumStatus::NotLorem, Rule::Ignored);
+    }
+    if config.skips(word.original()) {
+        return explain(IpsumStatus::NotLorem, Rule::TooShort);
+    }
+    if known {
+        return explain(IpsumStatus::NotLorem, Rule::DictionaryWord);
+    }
+    // Otherwise, pick the longest valid substring.
+    match longest().filter(|range| config.is_long_enough(&word.as_str()[range.clone()])) {
+        Some(range) => explain(word.partial(range.clone()), Rule::NotInDictionary).with_substring(word, range),
+        None => explain(IpsumStatus::FullyIpsum, Rule::NotInDictionary),
+    }
+}
+
+/// A lowercased word that remembers which character of the original produced each of its
+/// bytes. Lowercasing can change a word's byte length ('ẞ' becomes 'ß', 'İ' becomes "i̇"),
+/// so ranges found in the lowercase form must be mapped back before they are reported.
+pub(crate) struct LoweredWord<'a> {
+    original: &'a str,
+    lower: String,
+    /// For each byte of `lower`, the byte range of the original character it came from.
+    sources: Vec<Range<usize>>,
+}
+
+impl<'a> LoweredWord<'a> {
+    pub(crate) fn new(original: &'a str) -> Self {
+        let mut lower = String::with_capacity(original.len());
+        let mut sources = Vec::with_capacity(original.len());
+        for (start, c) in original.char_indices() {
+            let source = start..start + c.len_utf8();
+            for l in c.to_lowercase() {
+                lower.push(l);
+                sources.extend(std::iter::repeat_n(source.clone(), l.len_utf8()));
             }
         }
+        LoweredWord { original, lower, sources }
+    }
+
+    /// Like [`LoweredWord::new`], but keeps the case as it is when `case_sensitive` is set.
+    pub(crate) fn with_case(original: &'a str, case_sensitive: bool) -> Self {
+        if !case_sensitive {
+            return LoweredWord::new(original);
+        }
+        let sources = original
+            .char_indices()
+            .flat_map(|(start, c)| std::iter::repeat_n(start..start + c.len_utf8(), c.len_utf8()))
+            .collect();
+        LoweredWord { original, lower: original.to_string(), sources }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.lower
+    }
+
+    pub(crate) fn original(&self) -> &'a str {
+        self.original
+    }
+
+    /// The share of the word's characters that the substring at `range` of the lowercase form
+    /// covers. Characters rather than bytes are compared, so accented words are not penalized.
+    pub(crate) fn coverage(&self, range: Range<usize>) -> f64 {
+        self.lower[range].chars().count() as f64 / self.lower.chars().count() as f64
+    }
+
+    /// Maps a non-empty byte range of the lowercase form to the smallest range of whole
+    /// grapheme clusters in the original that produced it.
+    pub(crate) fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.sources[range.start].start;
+        let end = self.sources[range.end - 1].end;
+        let bounds = || {
+            self.original
+                .grapheme_indices(true)
+                .map(|(i, _)| i)
+                .chain([self.original.len()])
+        };
+        let start = bounds().take_while(|&i| i <= start).last().unwrap_or(0);
+        let end = bounds().find(|&i| i >= end).unwrap_or(self.original.len());
+        start..end
     }
 
-    match best {
-        Some((valid, range)) => IpsumStatus::Partial { valid, range },
-        None => IpsumStatus::FullyIpsum,
+    /// A [`IpsumStatus::Partial`] for the original text behind `range` of the lowercase form.
+    pub(crate) fn partial(&self, range: Range<usize>) -> IpsumStatus {
+        let range = self.original_range(range);
+        IpsumStatus::Partial { valid: self.original[range.clone()].to_string(), range }
     }
 }
 
-/// Scans the given text, splitting it into words and returning a vector with the starting
-/// and ending character positions (in the original text) along with the IpsumStatus for each word.
-pub fn scan_text(speller: &Speller, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
-    let mut results = Vec::new();
-    let mut index = 0;
-    for word in text.split_whitespace() {
-        let start = text[index..].find(word).map(|pos| index + pos).unwrap_or(index);
-        let end = start + word.len();
-        let status = classify_word_with_speller(speller, word);
-        results.push((start..end, status));
-        index = end; // advance index; note: for simplicity, we assume single spaces.
+/// Scans the given text, splitting it into words with [`tokenizer::words`] and returning a
+/// vector with the byte range of each word in the original text along with its IpsumStatus.
+/// Punctuation and numbers are skipped, and identifiers are split into their subwords, so
+/// "amet," is classified as "amet" and "loremIpsum" as "lorem" and "Ipsum".
+///
+/// Any [`Classifier`] decides; pass a [`Speller`] to classify against its dictionary alone.
+pub fn scan_text<C: Classifier + ?Sized>(classifier: &C, text: &str) -> Vec<(Range<usize>, IpsumStatus)> {
+    classifier.classify_text(text)
+}
+
+/// What [`remove_ipsum_with_gap`] leaves where filler was removed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Gap {
+    /// Remove the filler and nothing else; the whitespace around it stays exactly as it was.
+    Delete,
+    /// Remove the filler and tidy the whitespace around it: no doubled spaces, no trailing
+    /// spaces, and lines that held nothing but filler are removed along with a blank line
+    /// if that would otherwise leave two in a row.
+    #[default]
+    Collapse,
+    /// Replace each run of filler with this marker, leaving the whitespace around it alone.
+    Marker(String),
+}
+
+/// Punctuation that may sit between two filler words without ending the run, or close a
+/// filler sentence.
+fn is_run_punctuation(token: &str) -> bool {
+    matches!(token, "," | ";" | ":" | "." | "!" | "?" | "-" | "–" | "—" | "…")
+}
+
+fn is_inline_whitespace(token: &Token) -> bool {
+    token.kind == TokenKind::Whitespace && !token.text.contains('\n')
+}
+
+/// Finds the byte ranges of filler runs: filler words together with the inline whitespace and
+/// punctuation between them. A word such as "lorem_ipsum" is filler unless `classifier` finds
+/// all of its subwords real. A run that starts a sentence also takes the punctuation that
+/// closes it; a run that ends one takes the comma before it.
+fn filler_runs<C: Classifier + ?Sized>(classifier: &C, text: &str) -> Vec<Range<usize>> {
+    let tokens = tokenizer::tokenize(text);
+    let flagged: Vec<Range<usize>> = classifier
+        .classify_text(text)
+        .into_iter()
+        .filter(|(_, status)| status.is_filler())
+        .map(|(range, _)| range)
+        .collect();
+    let is_filler = |token: &Token| {
+        let first = flagged.partition_point(|range| range.end <= token.bytes.start);
+        token.kind == TokenKind::Word && flagged.get(first).is_some_and(|range| range.start < token.bytes.end)
+    };
+    let filler: Vec<bool> = tokens.iter().map(is_filler).collect();
+
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if !filler[i] {
+            i += 1;
+            continue;
+        }
+        let first = i;
+        let mut last = i;
+        let mut j = i + 1;
+        while j < tokens.len() {
+            if filler[j] {
+                last = j;
+            } else if !(is_inline_whitespace(&tokens[j]) || is_run_punctuation(tokens[j].text)) {
+                break;
+            }
+            j += 1;
+        }
+        let starts_sentence = tokens[..first]
+            .iter()
+            .rev()
+            .find(|t| t.kind != TokenKind::Whitespace || t.text.contains('\n'))
+            .is_none_or(|t| t.kind == TokenKind::Whitespace || matches!(t.text, "." | "!" | "?"));
+        let mut start = tokens[first].bytes.start;
+        if starts_sentence {
+            while last + 1 < tokens.len() && is_run_punctuation(tokens[last + 1].text) {
+                last += 1;
+            }
+        } else if tokens.get(last + 1).is_some_and(|t| matches!(t.text, "." | "!" | "?")) {
+            // "Hello, lorem." becomes "Hello." rather than "Hello,.".
+            let mut k = first;
+            while k > 0 && is_inline_whitespace(&tokens[k - 1]) {
+                k -= 1;
+            }
+            if k > 0 && matches!(tokens[k - 1].text, "," | ";" | ":") {
+                start = tokens[k - 1].bytes.start;
+            }
+        }
+        runs.push(start..tokens[last].bytes.end);
+        i = last + 1;
     }
-    results
+    runs
 }
 
-/// Removes words that are detected as ipsum (either fully or partially) from the input text.
-/// Returns a new string with only correct words.
-pub fn remove_ipsum(speller: &Speller, text: &str) -> String {
-    text.split_whitespace()
-        .filter(|word| classify_word_with_speller(speller, word) == IpsumStatus::NotLorem)
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Widens a filler run so that removing it leaves tidy whitespace, as [`Gap::Collapse`] describes.
+fn collapse_range(text: &str, run: Range<usize>) -> Range<usize> {
+    let line_start = text[..run.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[run.end..].find('\n').map_or(text.len(), |i| run.end + i);
+    let before = &text[line_start..run.start];
+    let after = &text[run.end..line_end];
+    let inline_after = after.len() - after.trim_start().len();
+    let inline_before = before.len() - before.trim_end().len();
+
+    match (before.trim().is_empty(), after.trim().is_empty()) {
+        // The run was the whole line: remove the line, and a blank line if it sat between two.
+        (true, true) => {
+            let is_blank = |line: &str| line.trim().is_empty();
+            let prev_blank = line_start == 0 || text[..line_start - 1].rsplit('\n').next().is_some_and(is_blank);
+            if line_end == text.len() {
+                return line_start.saturating_sub(1)..line_end;
+            }
+            let mut end = line_end + 1;
+            let next_line_end = text[end..].find('\n').map(|i| end + i);
+            if let Some(next_end) = next_line_end.filter(|&e| prev_blank && is_blank(&text[end..e])) {
+                end = next_end + 1;
+            }
+            line_start..end
+        }
+        // At the start of a line: keep the indentation, drop the space that followed.
+        (true, false) => run.start..run.end + inline_after,
+        // At the end of a line: drop the trailing space.
+        (false, true) => run.start - inline_before..line_end,
+        // In the middle: drop one side's space, keeping punctuation snug against its word.
+        (false, false) if inline_after > 0 && inline_before > 0 => run.start..run.end + inline_after,
+        (false, false) => run.start - inline_before..run.end,
+    }
 }
 
-/// For demonstration, a function that ipsum back in.
-/// This is a placeholder that, for every removed word, inserts a generic Lorem Ipsum phrase.
-pub fn add_ipsum(text: &str) -> String {
-    // For each word, if it is empty (i.e. removed), add a placeholder.
-    // Here we simply append a generic lorem ipsum phrase.
-    let ipsum_phrase = "lorem ipsum";
-    if text.is_empty() {
-        ipsum_phrase.to_string()
-    } else {
-        format!("{} {}", text, ipsum_phrase)
+/// Removes words that are detected as ipsum (either fully or partially) from the input text,
+/// preserving its layout; see [`Gap::Collapse`] and [`remove_ipsum_with_gap`].
+pub fn remove_ipsum<C: Classifier + ?Sized>(classifier: &C, text: &str) -> String {
+    remove_ipsum_with_gap(classifier, text, &Gap::Collapse)
+}
+
+/// Removes filler from the input text while keeping its line breaks, indentation and
+/// punctuation, filling each gap as `gap` says.
+///
+/// Filler is removed in runs: consecutive filler words along with the spaces and punctuation
+/// such as commas between them. A run that starts a sentence also takes its closing period, so
+/// a sentence of pure filler disappears entirely, while "Hello lorem." keeps its period.
+/// Brackets and quotes always stay, so string literals in source files remain balanced.
+/// Words are classified by `classifier` as in [`scan_text`].
+pub fn remove_ipsum_with_gap<C: Classifier + ?Sized>(classifier: &C, text: &str, gap: &Gap) -> String {
+    let runs = filler_runs(classifier, text);
+    let mut edits: Vec<(Range<usize>, &str)> = runs
+        .into_iter()
+        .map(|run| match gap {
+            Gap::Delete => (run, ""),
+            Gap::Collapse => (collapse_range(text, run), ""),
+            Gap::Marker(marker) => (run, marker.as_str()),
+        })
+        .collect();
+    edits.sort_by_key(|(range, _)| range.start);
+
+    let mut output = String::with_capacity(text.len());
+    let mut position = 0;
+    for (range, replacement) in edits {
+        // Widened ranges may overlap their neighbours; skip what is already removed.
+        let start = range.start.max(position);
+        output.push_str(&text[position..start]);
+        output.push_str(replacement);
+        position = position.max(range.end);
     }
+    output.push_str(&text[position..]);
+    output
+}
+
+/// The seed [`add_ipsum`] generates filler from.
+const DEFAULT_SEED: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+/// How many words [`add_ipsum_with_rng`] inserts at a time when inserting between words.
+const INSERTED_WORDS: RangeInclusive<usize> = 1..=3;
+
+/// One piece of filler inserted by [`add_ipsum_with_rng`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Insertion {
+    /// Byte offset in the clean text at which the filler was inserted.
+    pub offset: usize,
+    /// Byte range of the inserted filler, including its leading separator, in the output.
+    pub range: Range<usize>,
 }
 
+/// Text with filler inserted, and where it went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Injection {
+    /// The text with filler inserted.
+    pub text: String,
+    /// The insertions, in order of position.
+    pub insertions: Vec<Insertion>,
+}
+
+impl Injection {
+    /// Removes every insertion, giving back exactly the clean text.
+    pub fn revert(&self) -> String {
+        revert_insertions(&self.text, &self.insertions)
+    }
+}
+
+/// Removes the given insertions from `text`, e.g. with a map saved from an [`Injection`].
+/// Ranges that fall outside `text` are ignored.
+pub fn revert_insertions(text: &str, insertions: &[Insertion]) -> String {
+    let mut clean = String::with_capacity(text.len());
+    let mut position = 0;
+    for insertion in insertions {
+        if let Some(before) = text.get(position..insertion.range.start) {
+            clean.push_str(before);
+            position = insertion.range.end;
+        }
+    }
+    clean.push_str(text.get(position..).unwrap_or_default());
+    clean
+}
+
+/// The offsets in `text` where filler of the given unit may go, each with the separator that
+/// goes before the filler.
+fn insertion_points(text: &str, unit: TextUnit) -> Vec<(usize, &'static str)> {
+    let tokens = tokenizer::tokenize(text);
+    let followed_by_space = |i: usize| tokens.get(i + 1).is_some_and(|t| t.kind == TokenKind::Whitespace);
+    match unit {
+        // After a word that is followed by a space: "Hello world" becomes "Hello lorem world".
+        TextUnit::Words => (0..tokens.len())
+            .filter(|&i| tokens[i].is_word() && followed_by_space(i))
+            .map(|i| (tokens[i].bytes.end, " "))
+            .collect(),
+        // After the punctuation that closes a sentence, before a space or the end of the text.
+        TextUnit::Sentences => (0..tokens.len())
+            .filter(|&i| matches!(tokens[i].text, "." | "!" | "?"))
+            .filter(|&i| followed_by_space(i) || i + 1 == tokens.len())
+            .map(|i| (tokens[i].bytes.end, " "))
+            .collect(),
+        // At every blank line, and after the last paragraph.
+        TextUnit::Paragraphs => {
+            let mut points: Vec<(usize, &'static str)> = tokens
+                .iter()
+                .filter(|t| t.kind == TokenKind::Whitespace && t.text.matches('\n').count() >= 2)
+                .map(|t| (t.bytes.start, "\n\n"))
+                .collect();
+            let end = text.trim_end().len();
+            if end > 0 && points.last().is_none_or(|&(at, _)| at != end) {
+                points.push((end, "\n\n"));
+            }
+            points
+        }
+    }
+}
+
+/// Inserts generated filler into clean text at word, sentence or paragraph boundaries,
+/// depending on `unit`, leaving every byte of the original in place.
+///
+/// Each possible position receives filler with probability `density` (clamped to `[0, 1]`):
+/// one to three bare words between words, a sentence shaped by `shape` after a sentence, or
+/// a paragraph at a blank line or the end of the text. The returned [`Injection`] records every
+/// insertion, so [`Injection::revert`] gives back the original exactly.
+/// Returns [`GenerateError::EmptyModel`] if the model has nothing to generate from, and
+/// [`GenerateError::NoWords`] if it only generates punctuation.
+pub fn add_ipsum_with_rng<R: Rng + ?Sized>(
+    text: &str,
+    model: &MarkovModel,
+    unit: TextUnit,
+    density: f64,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<Injection, GenerateError> {
+    if model.is_empty() {
+        return Err(GenerateError::EmptyModel);
+    }
+    let density = if density.is_nan() { 0.0 } else { density.clamp(0.0, 1.0) };
+    let mut output = String::with_capacity(text.len());
+    let mut insertions = Vec::new();
+    let mut position = 0;
+    for (offset, separator) in insertion_points(text, unit) {
+        if !rng.random_bool(density) {
+            continue;
+        }
+        let filler = match unit {
+            TextUnit::Words => {
+                let count = rng.random_range(INSERTED_WORDS);
+                structure::draw_words(model, count, rng)?.join(" ")
+            }
+            TextUnit::Sentences => structure::generate_sentence_with_rng(model, shape, rng)?,
+            TextUnit::Paragraphs => structure::generate_paragraph_with_rng(model, shape, rng)?,
+        };
+        if filler.is_empty() {
+            continue;
+        }
+        output.push_str(&text[position..offset]);
+        let start = output.len();
+        output.push_str(separator);
+        output.push_str(&filler);
+        insertions.push(Insertion { offset, range: start..output.len() });
+        position = offset;
+    }
+    output.push_str(&text[position..]);
+    Ok(Injection { text: output, insertions })
+}
+
+/// Inserts a few generated Lorem Ipsum words between the words of `text`, at roughly one
+/// position in four. Use [`add_ipsum_with_rng`] to choose the model, unit and density and to
+/// get the insertion map back.
+pub fn add_ipsum(text: &str) -> String {
+    let model = MarkovModel::train(DEFAULT_SEED, 1);
+    add_ipsum_with_rng(text, &model, TextUnit::Words, 0.25, &TextShape::default(), &mut rand::rng())
+        .map(|injection| injection.text)
+        .unwrap_or_else(|_| text.to_string())
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashSet;
+    use crate::ipsum_classifier::classify_word;
+    use crate::lorem::seeded_rng;
+    use std::collections::HashMap;
 
     const SEED: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
 
     #[test]
     fn test_generate_lorem_sample_produces_ipsum() {
         // Generate a sample of 50 words using the seed.
-        let sample = crate::lorem::generate_lorem_sample(SEED, 50);
+        let sample = crate::lorem::generate_lorem_sample_with_rng(SEED, 50, &mut crate::lorem::seeded_rng(7)).unwrap();
         println!("Generated sample: {}", sample);
         // The generated sample should look like Lorem Ipsum,
         // so the detector should say it is ipsum-like.
         assert!(
-            crate::lorem::detect_lorem_ipsum(&sample, SEED, 50),
+            crate::lorem::detect_lorem_ipsum_with_rng(&sample, SEED, 50, &mut crate::lorem::seeded_rng(7)),
             "The generated sample was not detected as ipsum-like"
         );
     }
@@ -171,11 +523,142 @@ mod tests {
 
     #[test]
     fn test_classify_word_for_ipsum_words() {
-        let corpus: HashSet<&str> = crate::dolor::corpus();
+        let mut speller = Speller {
+            letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            n_words: HashMap::new(),
+        };
+        speller.train("tomato potato");
         // Words from the corpus (filler) should be classified as FullyIpsum.
-        assert_eq!(classify_word("lorem", &corpus), IpsumStatus::FullyIpsum);
-        assert_eq!(classify_word("ipsum", &corpus), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word(&speller, "lorem"), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word(&speller, "ipsum"), IpsumStatus::FullyIpsum);
         // A non-ipsum word (e.g. "tomato") should be classified as NotLorem.
-        assert_eq!(classify_word("tomato", &corpus), IpsumStatus::NotLorem);
+        assert_eq!(classify_word(&speller, "tomato"), IpsumStatus::NotLorem);
+    }
+
+    #[test]
+    fn test_non_ascii_ranges_are_valid_in_the_original() {
+        let mut speller = Speller {
+            letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            n_words: HashMap::new(),
+        };
+        for word in ["café", "straße", "cafe", "tomato"] {
+            speller.n_words.insert(word.to_string(), 1);
+        }
+        let dictionary = WordMatcher::from_speller(&speller);
+        let cases = [
+            // 'É' and 'é' are both two bytes.
+            ("CAFÉS", 0..5, "CAFÉ"),
+            // 'ẞ' is three bytes but lowercases to the two-byte 'ß'.
+            ("STRAẞEN", 0..8, "STRAẞE"),
+            // A decomposed 'é' must not be split from its combining accent.
+            ("cafe\u{301}s", 0..6, "cafe\u{301}"),
+            ("🍅tomato🍅", 4..10, "tomato"),
+        ];
+        for (word, range, valid) in cases {
+            let expected = IpsumStatus::Partial { valid: valid.to_string(), range: range.clone() };
+            assert_eq!(classify_word_with_speller(&speller, word), expected, "{}", word);
+            assert_eq!(classify_word_with_matcher(&dictionary, word), expected, "{}", word);
+            assert_eq!(&word[range], valid);
+        }
+        assert_eq!(classify_word_with_speller(&speller, "Café"), IpsumStatus::NotLorem);
+        assert_eq!(classify_word_with_speller(&speller, "ünïcödé"), IpsumStatus::FullyIpsum);
+
+        let text = "Ein\u{a0}Café\u{3000}🍅 straße\tnaïve";
+        let scanned = scan_text(&speller, text);
+        let words: Vec<&str> = scanned.iter().map(|(range, _)| &text[range.clone()]).collect();
+        assert_eq!(words, ["Ein", "Café", "straße", "naïve"]);
+        assert_eq!(scanned[1].1, IpsumStatus::NotLorem);
+        // The emoji has no words, so it is kept.
+        assert_eq!(remove_ipsum(&speller, text), "Café\u{3000}🍅 straße");
+    }
+
+    #[test]
+    fn test_scan_text_separates_punctuation() {
+        let mut speller = Speller {
+            letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            n_words: HashMap::new(),
+        };
+        speller.train("tomato potato soup");
+        let text = "Tomato, lorem_ipsum and (potato)   soup.";
+        let scanned = scan_text(&speller, text);
+        let words: Vec<&str> = scanned.iter().map(|(range, _)| &text[range.clone()]).collect();
+        assert_eq!(words, ["Tomato", "lorem", "ipsum", "and", "potato", "soup"]);
+        assert_eq!(scanned[0].1, IpsumStatus::NotLorem);
+        assert_eq!(scanned[5].1, IpsumStatus::NotLorem);
+        assert_eq!(remove_ipsum(&speller, text), "Tomato, (potato)   soup.");
+    }
+
+    #[test]
+    fn test_remove_ipsum_preserves_layout() {
+        let mut speller = Speller {
+            letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            n_words: HashMap::new(),
+        };
+        speller.train("title intro text here item one indented code final words hello world let label");
+        let text = "# Title\n\nIntro text here.\n\nLorem ipsum dolor sit amet, consectetur adipiscing elit.\n\n- item one\n- lorem ipsum\n    indented code lorem\nFinal words, lorem ipsum.\nHello lorem world. Lorem ipsum. Hello.\nlet label = \"Lorem ipsum\";\n";
+
+        assert_eq!(
+            remove_ipsum_with_gap(&speller, text, &Gap::Delete),
+            "# Title\n\nIntro text here.\n\n\n\n- item one\n- \n    indented code \nFinal words.\nHello  world.  Hello.\nlet label = \"\";\n"
+        );
+        let collapsed = "# Title\n\nIntro text here.\n\n- item one\n-\n    indented code\nFinal words.\nHello world. Hello.\nlet label = \"\";\n";
+        assert_eq!(remove_ipsum_with_gap(&speller, text, &Gap::Collapse), collapsed);
+        assert_eq!(remove_ipsum(&speller, text), collapsed);
+        assert_eq!(
+            remove_ipsum_with_gap(&speller, text, &Gap::Marker("[…]".to_string())),
+            "# Title\n\nIntro text here.\n\n[…]\n\n- item one\n- […]\n    indented code […]\nFinal words[…].\nHello […] world. […] Hello.\nlet label = \"[…]\";\n"
+        );
+
+        // Text without filler comes back unchanged, whatever the gap.
+        let clean = "  Hello,\tworld.\r\n\n";
+        for gap in [Gap::Delete, Gap::Collapse, Gap::Marker("X".to_string())] {
+            assert_eq!(remove_ipsum_with_gap(&speller, clean, &gap), clean);
+        }
+        assert_eq!(remove_ipsum(&speller, "Lorem ipsum."), "");
+        assert_eq!(remove_ipsum(&speller, "Hello.\n\nLorem ipsum."), "Hello.\n");
+    }
+
+    #[test]
+    fn test_add_ipsum_is_reversible() {
+        let model = MarkovModel::train(SEED, 1);
+        let shape = TextShape::default();
+        let text = "# Notes\n\nThe build is green. Ship it!\nNext, update the docs?\n\nThanks, team.\n";
+        for unit in [TextUnit::Words, TextUnit::Sentences, TextUnit::Paragraphs] {
+            let injection = add_ipsum_with_rng(text, &model, unit, 0.6, &shape, &mut seeded_rng(7)).unwrap();
+            assert!(!injection.insertions.is_empty(), "{:?}", unit);
+            assert_eq!(injection.revert(), text, "{:?}", unit);
+            for insertion in &injection.insertions {
+                let inserted = &injection.text[insertion.range.clone()];
+                assert!(tokenizer::words(inserted).all(|w| SEED.to_lowercase().contains(&w.text.to_lowercase())), "{}", inserted);
+            }
+            // The map survives a round trip through JSON and can be applied on its own.
+            let map: Vec<Insertion> = serde_json::from_str(&serde_json::to_string(&injection.insertions).unwrap()).unwrap();
+            assert_eq!(revert_insertions(&injection.text, &map), text);
+            let again = add_ipsum_with_rng(text, &model, unit, 0.6, &shape, &mut seeded_rng(7)).unwrap();
+            assert_eq!(again, injection);
+        }
+    }
+
+    #[test]
+    fn test_add_ipsum_positions_and_density() {
+        let model = MarkovModel::train(SEED, 1);
+        let shape = TextShape::default();
+        let text = "One two. Three four!\n\nFive six";
+        let add = |unit, density| add_ipsum_with_rng(text, &model, unit, density, &shape, &mut seeded_rng(3)).unwrap();
+
+        assert_eq!(add(TextUnit::Words, 0.0).text, text);
+        assert!(add(TextUnit::Words, f64::NAN).insertions.is_empty());
+        let offsets = |unit| add(unit, 1.0).insertions.iter().map(|i| i.offset).collect::<Vec<_>>();
+        assert_eq!(offsets(TextUnit::Words), [3, 14, 26]);
+        assert_eq!(offsets(TextUnit::Sentences), [8, 20]);
+        assert_eq!(offsets(TextUnit::Paragraphs), [20, text.len()]);
+
+        let paragraphs = add(TextUnit::Paragraphs, 1.0);
+        assert!(paragraphs.text.starts_with("One two. Three four!\n\n"));
+        assert!(paragraphs.text.contains("\n\nFive six\n\n"));
+        assert_eq!(
+            add_ipsum_with_rng(text, &MarkovModel::train("", 1), TextUnit::Words, 1.0, &shape, &mut seeded_rng(3)),
+            Err(GenerateError::EmptyModel)
+        );
     }
 }
@@ -1,121 +1,482 @@
-use std::collections::HashSet;
+use std::fmt;
 use std::ops::Range;
-use crate::ipsum::IpsumStatus;
+use crate::config::ClassifierConfig;
+use crate::dolor;
+use crate::fuzzy::{self, FuzzyOptions};
+use crate::ipsum::{is_known_word, IpsumStatus, LoweredWord};
+use crate::matcher::{longest_known_substring, WordMatcher};
 use spellcheck::Speller;
-/// Classifies a word as either NotLorem, FullyIpsum, or Partial, using the given corpus.
-/// 
-/// - If the entire word (in lowercase) is found in the corpus, it's considered FullyIpsum.
-/// - Otherwise, we search all substrings of the word. If the longest valid substring (found in the corpus)
-///   covers at least 70% of the word, it's classified as Partial; if not, the word is fully filler (FullyIpsum).
-/// - If no substring is found, the word is considered NotLorem.
-///
-/// # Example
-/// ```
-/// # use std::collections::HashSet;
-/// # use ipsum_classifier::{classify_word, IpsumStatus};
-/// # let corpus: HashSet<&'static str> = ["lorem", "ipsum", "dolor", "sit", "amet"].iter().cloned().collect();
-/// assert_eq!(classify_word("lorem", &corpus), IpsumStatus::FullyIpsum);
-/// // For "loremps", if "lorem" (5 letters) is found and "loremps" has 7 letters, 5/7 ≈ 0.71:
-/// if let IpsumStatus::Partial { valid, range } = classify_word("loremps", &corpus) {
-///     assert_eq!(valid, "lorem");
-///     assert_eq!(range, 0..5);
-/// }
-
 /// Classifies a word as either NotLorem, FullyIpsum, or Partial.
 /// 
-/// This function loads the corpus (the "dolar corpus") directly from the `dolar` module.
+/// The corpus (the "dolar corpus") comes from [`dolor::corpus_matcher`], which is compiled once
+/// and shared. To classify many words, build an [`IpsumClassifier`] instead.
 /// 
 /// - If the entire lowercased word is in the corpus, it's marked as FullyIpsum.
 /// - Otherwise, if the spellchecker recognizes the entire word (i.e. it exists in `speller.n_words`),
 ///   it is marked as NotLorem.
 /// - Otherwise, we search for valid substrings (from `speller.n_words`). If the longest such substring
-///   covers at least 70% of the word’s length:
+///   covers at least [`ClassifierConfig::min_partial_ratio`] (70% by default) of the word’s length:
 ///     - If that substring is itself in the corpus, we treat the word as FullyIpsum;
 ///     - Otherwise, it is classified as Partial.
 /// - If no valid substring is found, the word is NotLorem.
+///
+/// # Example
+/// ```
+/// # use std::collections::HashMap;
+/// # use spellcheck::Speller;
+/// use lorem_ipsumlike::ipsum::IpsumStatus;
+/// use lorem_ipsumlike::ipsum_classifier::classify_word;
+///
+/// let mut speller = Speller { letters: "abcdefghijklmnopqrstuvwxyz".to_string(), n_words: HashMap::new() };
+/// speller.train("tomato potato");
+/// assert_eq!(classify_word(&speller, "lorem"), IpsumStatus::FullyIpsum);
+/// // "tomatox" has 7 letters and contains "tomato" (6 letters), 6/7 ≈ 0.86:
+/// if let IpsumStatus::Partial { valid, range } = classify_word(&speller, "tomatox") {
+///     assert_eq!(valid, "tomato");
+///     assert_eq!(range, 0..6);
+/// }
+/// ```
 pub fn classify_word(speller: &Speller, word: &str) -> IpsumStatus {
-    // Load the corpus directly.
-    let corpus: HashSet<&'static str> = crate::dolor::corpus();
-    let lower = word.to_lowercase();
+    classify_word_with_config(speller, word, &ClassifierConfig::default())
+}
 
-    // If the whole word is in the corpus, it's filler.
-    if corpus.contains(lower.as_str()) {
-        return IpsumStatus::FullyIpsum;
+/// [`classify_word`] with the thresholds, word lists and case handling of `config`.
+pub fn classify_word_with_config(speller: &Speller, word: &str, config: &ClassifierConfig) -> IpsumStatus {
+    classify_explain_with_config(speller, word, config).status
+}
+
+/// [`classify_word`], but a word that would be real only because it is a misspelled corpus
+/// word ("lorm", "consecteur") is filler. See [`fuzzy::closest_corpus_word`] for which
+/// misspellings `options` accept.
+pub fn classify_word_fuzzy(speller: &Speller, word: &str, options: &FuzzyOptions) -> IpsumStatus {
+    classify_word_fuzzy_with_config(speller, word, options, &ClassifierConfig::default())
+}
+
+/// [`classify_word_fuzzy`] with the thresholds, word lists and case handling of `config`.
+/// Words the configuration skips are never matched, and with `case_sensitive` set a capital
+/// counts as an edit away from the lowercase corpus.
+pub fn classify_word_fuzzy_with_config(speller: &Speller, word: &str, options: &FuzzyOptions, config: &ClassifierConfig) -> IpsumStatus {
+    match classify_word_with_config(speller, word, config) {
+        IpsumStatus::NotLorem if is_misspelled_corpus_word(word, options, config, |w| speller.n_words.contains_key(w)) => {
+            IpsumStatus::FullyIpsum
+        }
+        status => status,
     }
+}
 
-    // If the entire word is recognized by the spellchecker, it is considered real.
-    if speller.n_words.contains_key(&lower) {
-        return IpsumStatus::NotLorem;
+/// Whether `word` is close to a corpus word without being one the configuration skips or a
+/// word `is_word` accepts.
+fn is_misspelled_corpus_word(word: &str, options: &FuzzyOptions, config: &ClassifierConfig, is_word: impl Fn(&str) -> bool) -> bool {
+    let lower = LoweredWord::with_case(word, config.case_sensitive);
+    !config.skips(word) && !is_known_word(lower.as_str(), is_word) && fuzzy::closest_corpus_word_with_case(lower.as_str(), options).is_some()
+}
+
+/// [`classify_word`] with the speller's dictionary compiled into a [`WordMatcher`] up front,
+/// so classifying each word takes time linear in its length. Build one per dictionary and
+/// reuse it when classifying many words.
+#[derive(Debug, Clone)]
+pub struct IpsumClassifier {
+    dictionary: WordMatcher,
+    config: ClassifierConfig,
+}
+
+impl IpsumClassifier {
+    /// Compiles the dictionary of `speller`, with the default configuration.
+    pub fn new(speller: &Speller) -> Self {
+        IpsumClassifier { dictionary: WordMatcher::from_speller(speller), config: ClassifierConfig::default() }
     }
 
-    // Otherwise, search for valid substrings from the spellcheck dictionary.
-    let mut best: Option<(String, Range<usize>)> = None;
-    for i in 0..lower.len() {
-        for j in (i + 1)..=lower.len() {
-            let sub = &lower[i..j];
-            if speller.n_words.contains_key(sub) {
-                best = match best {
-                    Some((ref best_str, ref best_range)) => {
-                        if sub.len() > best_str.len() {
-                            Some((sub.to_string(), i..j))
-                        } else {
-                            Some((best_str.clone(), best_range.clone()))
-                        }
-                    }
-                    None => Some((sub.to_string(), i..j)),
-                };
+    /// Sets the thresholds, word lists and case handling.
+    pub fn with_config(mut self, config: ClassifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The thresholds, word lists and case handling.
+    pub fn config(&self) -> &ClassifierConfig {
+        &self.config
+    }
+
+    /// Classifies a word exactly as [`classify_word_with_config`] would with the same speller
+    /// and configuration.
+    pub fn classify(&self, word: &str) -> IpsumStatus {
+        self.explain(word).status
+    }
+
+    /// Explains a word as [`classify_explain_with_config`] would with the same speller and
+    /// configuration, except that the compiled dictionary has no counts to give a
+    /// [`frequency`](Explanation::frequency); see [`Explanation::with_frequency`].
+    pub fn explain(&self, word: &str) -> Explanation {
+        let lower = LoweredWord::with_case(word, self.config.case_sensitive);
+        let known = is_known_word(lower.as_str(), |w| self.dictionary.contains(w));
+        decide(&lower, known, || self.dictionary.longest_match(lower.as_str()), &self.config)
+    }
+
+    /// Classifies a word exactly as [`classify_word_fuzzy_with_config`] would with the same
+    /// speller and configuration.
+    pub fn classify_fuzzy(&self, word: &str, options: &FuzzyOptions) -> IpsumStatus {
+        match self.classify(word) {
+            IpsumStatus::NotLorem if is_misspelled_corpus_word(word, options, &self.config, |w| self.dictionary.contains(w)) => {
+                IpsumStatus::FullyIpsum
             }
+            status => status,
         }
     }
+}
 
-    if let Some((valid, range)) = best {
-        let ratio = valid.len() as f64 / lower.len() as f64;
-        // If the valid substring is itself a known filler word, treat the word as fully filler.
-        if corpus.contains(valid.as_str()) {
-            return IpsumStatus::FullyIpsum;
+/// Which rule decided a word. Most are the rules of [`classify_word`]; the last few belong to
+/// the other [`Classifier`](crate::classifier::Classifier)s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// The word is on the configuration's ignore list, so it is real.
+    Ignored,
+    /// The word is shorter than the configuration's minimum word length, so it is real.
+    TooShort,
+    /// The whole word is a corpus word, so it is filler.
+    CorpusWord,
+    /// The whole word is in the dictionary, so it is real.
+    DictionaryWord,
+    /// The longest dictionary word inside the word is a corpus word, so it is filler.
+    CorpusSubstring,
+    /// The longest dictionary word inside the word covers enough of it to be partly real.
+    PartialMatch,
+    /// The longest dictionary word inside the word covers too little of it, so it is filler.
+    ShortMatch,
+    /// No dictionary word occurs inside the word, so it is taken to be real.
+    NoMatch,
+    /// The word is not in the dictionary, so it is filler; only in part if a dictionary word
+    /// occurs inside it. Used by the classifiers that check the dictionary alone.
+    NotInDictionary,
+    /// The longest corpus word inside the word covers enough of it to make it filler. Used by
+    /// [`CorpusClassifier`](crate::classifier::CorpusClassifier).
+    MostlyCorpusWord,
+    /// No corpus word covers enough of the word, so it is real. Used by
+    /// [`CorpusClassifier`](crate::classifier::CorpusClassifier).
+    NotCorpusWord,
+    /// The word is shared by Latin and English, so its neighbours in the sentence decided it:
+    /// it is filler if more of them are filler than real. Used by
+    /// [`ContextClassifier`](crate::context::ContextClassifier).
+    Context {
+        /// How many neighbours are filler.
+        filler: usize,
+        /// How many neighbours are real.
+        real: usize,
+    },
+    /// The classifier gave no reason.
+    Unexplained,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Rule::Ignored => "ignored",
+            Rule::TooShort => "too-short",
+            Rule::CorpusWord => "corpus-word",
+            Rule::DictionaryWord => "dictionary-word",
+            Rule::CorpusSubstring => "corpus-substring",
+            Rule::PartialMatch => "partial-match",
+            Rule::ShortMatch => "short-match",
+            Rule::NoMatch => "no-match",
+            Rule::NotInDictionary => "not-in-dictionary",
+            Rule::MostlyCorpusWord => "mostly-corpus-word",
+            Rule::NotCorpusWord => "not-corpus-word",
+            Rule::Context { .. } => "context",
+            Rule::Unexplained => "unexplained",
+        })
+    }
+}
+
+/// Why [`classify_explain`], or a classifier's
+/// [`explain_word`](crate::classifier::Classifier::explain_word), classified a word as it did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The classification, the same as [`classify_word`] returns.
+    pub status: IpsumStatus,
+    /// The rule that decided it.
+    pub rule: Rule,
+    /// The longest dictionary word inside the word, as it appears in the word, and its byte
+    /// range there; for the corpus rules, the longest corpus word. Only looked for when no
+    /// whole-word rule applies.
+    pub substring: Option<(String, Range<usize>)>,
+    /// The share of the word's characters that `substring` covers.
+    pub ratio: Option<f64>,
+    /// How often the dictionary has seen the word, or `substring` if there is one.
+    pub frequency: Option<u32>,
+    /// The ratio at or above which a substring makes the word partly real.
+    pub min_ratio: f64,
+    /// The fewest characters a substring needs to count.
+    pub min_substring_len: usize,
+}
+
+impl Explanation {
+    /// An explanation by `rule` alone, with the thresholds of `config`.
+    pub(crate) fn new(status: IpsumStatus, rule: Rule, config: &ClassifierConfig) -> Self {
+        Explanation {
+            status,
+            rule,
+            substring: None,
+            ratio: None,
+            frequency: None,
+            min_ratio: config.min_partial_ratio,
+            min_substring_len: config.min_substring_len,
         }
-        if ratio >= 0.7 {
-            IpsumStatus::Partial { valid, range }
-        } else {
-            IpsumStatus::FullyIpsum
+    }
+
+    /// A status some classifier gave without a reason.
+    pub(crate) fn unexplained(status: IpsumStatus) -> Self {
+        Explanation::new(status, Rule::Unexplained, &ClassifierConfig::default())
+    }
+
+    /// Records the substring of `word` at `range` of its lowercase form, and how much of the
+    /// word it covers.
+    pub(crate) fn with_substring(self, word: &LoweredWord, range: Range<usize>) -> Self {
+        let original = word.original_range(range.clone());
+        Explanation {
+            substring: Some((word.original()[original.clone()].to_string(), original)),
+            ratio: Some(word.coverage(range)),
+            ..self
         }
-    } else {
-        IpsumStatus::NotLorem
+    }
+
+    /// Fills in [`frequency`](Explanation::frequency) from the counts of `speller`: those of
+    /// the substring if there is one, and of `word` otherwise, lowercased.
+    pub fn with_frequency(mut self, speller: &Speller, word: &str) -> Self {
+        let key = self.substring.as_ref().map_or(word, |(substring, _)| substring);
+        self.frequency = speller.n_words.get(&key.to_lowercase()).copied();
+        self
     }
 }
 
+impl fmt::Display for Explanation {
+    /// Formats the explanation as `key=value` pairs, leaving out what does not apply:
+    /// `rule=partial-match substring="tomato" range=0..6 ratio=0.86 frequency=1 min_ratio=0.70 min_substring_len=1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rule={}", self.rule)?;
+        if let Rule::Context { filler, real } = self.rule {
+            write!(f, " filler_neighbours={} real_neighbours={}", filler, real)?;
+        }
+        if let Some((substring, range)) = &self.substring {
+            write!(f, " substring={:?} range={}..{}", substring, range.start, range.end)?;
+        }
+        if let Some(ratio) = self.ratio {
+            write!(f, " ratio={:.2}", ratio)?;
+        }
+        if let Some(frequency) = self.frequency {
+            write!(f, " frequency={}", frequency)?;
+        }
+        write!(f, " min_ratio={:.2} min_substring_len={}", self.min_ratio, self.min_substring_len)
+    }
+}
+
+/// [`classify_word`], along with the rule that decided it and the numbers it was decided on.
+///
+/// # Example
+/// ```
+/// # use std::collections::HashMap;
+/// # use spellcheck::Speller;
+/// use lorem_ipsumlike::ipsum::IpsumStatus;
+/// use lorem_ipsumlike::ipsum_classifier::{classify_explain, Rule};
+///
+/// let mut speller = Speller { letters: "abcdefghijklmnopqrstuvwxyz".to_string(), n_words: HashMap::new() };
+/// speller.train("tomato potato");
+/// let explanation = classify_explain(&speller, "tomatoxyz");
+/// assert_eq!(explanation.status, IpsumStatus::FullyIpsum);
+/// // "tomato" covers only 6 of the 9 letters.
+/// assert_eq!(explanation.rule, Rule::ShortMatch);
+/// assert_eq!(explanation.substring, Some(("tomato".to_string(), 0..6)));
+/// ```
+pub fn classify_explain(speller: &Speller, word: &str) -> Explanation {
+    classify_explain_with_config(speller, word, &ClassifierConfig::default())
+}
+
+/// [`classify_explain`] with the thresholds, word lists and case handling of `config`.
+pub fn classify_explain_with_config(speller: &Speller, word: &str, config: &ClassifierConfig) -> Explanation {
+    let lower = LoweredWord::with_case(word, config.case_sensitive);
+    let known = is_known_word(lower.as_str(), |w| speller.n_words.contains_key(w));
+    decide(&lower, known, || longest_known_substring(&speller.n_words, lower.as_str()), config).with_frequency(speller, word)
+}
+
+/// The rules shared by [`classify_word`] and [`IpsumClassifier::classify`], given whether the
+/// dictionary knows the whole word and a way to find the longest dictionary word inside it.
+/// The explanation's frequency is left for the caller, who has the counts.
+fn decide(
+    word: &LoweredWord,
+    known: bool,
+    longest: impl FnOnce() -> Option<Range<usize>>,
+    config: &ClassifierConfig,
+) -> Explanation {
+    let corpus = dolor::corpus_matcher();
+    let lower = word.as_str();
+    let explain = |status, rule| Explanation::new(status, rule, config);
+
+    if config.is_ignored(word.original()) {
+        return explain(IpsumStatus::NotLorem, Rule::Ignored);
+    }
+    if config.skips(word.original()) {
+        return explain(IpsumStatus::NotLorem, Rule::TooShort);
+    }
+
+    // If the whole word is in the corpus, it's filler.
+    if corpus.contains(lower) {
+        return explain(IpsumStatus::FullyIpsum, Rule::CorpusWord);
+    }
+
+    // If the entire word is recognized by the spellchecker, it is considered real.
+    if known {
+        return explain(IpsumStatus::NotLorem, Rule::DictionaryWord);
+    }
+
+    // Otherwise, search for valid substrings from the spellcheck dictionary.
+    let Some(range) = longest().filter(|range| config.is_long_enough(&lower[range.clone()])) else {
+        return explain(IpsumStatus::NotLorem, Rule::NoMatch);
+    };
+    let valid = &lower[range.clone()];
+    let ratio = word.coverage(range.clone());
+    let (status, rule) = if corpus.contains(valid) {
+        // If the valid substring is itself a known filler word, treat the word as fully filler.
+        (IpsumStatus::FullyIpsum, Rule::CorpusSubstring)
+    } else if ratio >= config.min_partial_ratio {
+        (word.partial(range.clone()), Rule::PartialMatch)
+    } else {
+        (IpsumStatus::FullyIpsum, Rule::ShortMatch)
+    };
+    explain(status, rule).with_substring(word, range)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    // Import the dolar corpus module – here we assume you have a module `dolar` that defines `corpus()`.
-    // In your crate, ensure that `pub mod dolar;` is declared in lib.rs.
-    use crate::dolor::corpus;
+    use std::collections::HashMap;
+
+    /// Helper function: creates a speller that knows a couple of real words.
+    fn train_speller() -> Speller {
+        let mut speller = Speller {
+            letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            n_words: HashMap::new(),
+        };
+        speller.train("tomato potato lorem");
+        speller
+    }
 
     #[test]
     fn test_classify_fully_ipsum() {
-        let corp = corpus();
+        let speller = train_speller();
         // "lorem", "ipsum", "dolor", etc. should be flagged as filler.
-        assert_eq!(classify_word("lorem", &corp), IpsumStatus::FullyIpsum);
-        assert_eq!(classify_word("ipsum", &corp), IpsumStatus::FullyIpsum);
-        assert_eq!(classify_word("dolor", &corp), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word(&speller, "lorem"), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word(&speller, "ipsum"), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word(&speller, "dolor"), IpsumStatus::FullyIpsum);
+        // "loremps" contains "lorem", which is itself a filler word.
+        assert_eq!(classify_word(&speller, "loremps"), IpsumStatus::FullyIpsum);
     }
 
     #[test]
     fn test_classify_not_lorem() {
-        let corp = corpus();
+        let speller = train_speller();
         // A word not in the corpus should be considered real.
-        assert_eq!(classify_word("tomato", &corp), IpsumStatus::NotLorem);
+        assert_eq!(classify_word(&speller, "tomato"), IpsumStatus::NotLorem);
+    }
+
+    #[test]
+    fn test_compiled_classifier_agrees() {
+        let speller = train_speller();
+        let classifier = IpsumClassifier::new(&speller);
+        for word in ["lorem", "Ipsum", "loremps", "tomato", "tomatox", "xxtomatoxx", "banana", "potatomato", ""] {
+            assert_eq!(classifier.classify(word), classify_word(&speller, word), "{}", word);
+        }
     }
 
     #[test]
     fn test_classify_partial() {
-        let corp = corpus();
-        // "loremps" is 7 characters and contains "lorem" (5 characters). 5/7 ≈ 0.71, so we expect Partial.
-        if let IpsumStatus::Partial { valid, range } = classify_word("loremps", &corp) {
-            assert_eq!(valid, "lorem");
-            assert_eq!(range, 0..5);
+        let speller = train_speller();
+        // "tomatox" is 7 characters and contains "tomato" (6 characters). 6/7 ≈ 0.86, so we expect Partial.
+        if let IpsumStatus::Partial { valid, range } = classify_word(&speller, "tomatox") {
+            assert_eq!(valid, "tomato");
+            assert_eq!(range, 0..6);
         } else {
-            panic!("Expected Partial classification for 'loremps'");
+            panic!("Expected Partial classification for 'tomatox'");
+        }
+    }
+
+    #[test]
+    fn test_classify_non_ascii() {
+        let speller = train_speller();
+        let classifier = IpsumClassifier::new(&speller);
+        // The ratio counts characters: "tomato" is 6 of the 8 characters (but only 6 of 10 bytes).
+        let expected = IpsumStatus::Partial { valid: "TOMATO".to_string(), range: 0..6 };
+        assert_eq!(classify_word(&speller, "TOMATOÉÉ"), expected);
+        assert_eq!(classifier.classify("TOMATOÉÉ"), expected);
+        for word in ["lørem", "ÍPSUM", "dolor™", "🍅🍅", "ẞ"] {
+            assert_eq!(classifier.classify(word), classify_word(&speller, word), "{}", word);
+        }
+        assert_eq!(classify_word(&speller, "LOREM"), IpsumStatus::FullyIpsum);
+    }
+
+    #[test]
+    fn test_classify_explain() {
+        let mut speller = train_speller();
+        speller.train("tomato ad");
+        let cases = [
+            ("Lorem", Rule::CorpusWord, None, Some(1)),
+            ("tomato", Rule::DictionaryWord, None, Some(2)),
+            ("xxadx", Rule::CorpusSubstring, Some(("ad", 2..4)), Some(1)),
+            ("TOMATOx", Rule::PartialMatch, Some(("TOMATO", 0..6)), Some(2)),
+            ("potatoxyzzy", Rule::ShortMatch, Some(("potato", 0..6)), Some(1)),
+            ("xyzzy", Rule::NoMatch, None, None),
+        ];
+        for (word, rule, substring, frequency) in cases {
+            let explanation = classify_explain(&speller, word);
+            assert_eq!(explanation.status, classify_word(&speller, word), "{}", word);
+            assert_eq!(explanation.rule, rule, "{}", word);
+            assert_eq!(explanation.ratio.is_some(), substring.is_some(), "{}", word);
+            assert_eq!(explanation.substring, substring.map(|(s, r)| (s.to_string(), r)), "{}", word);
+            assert_eq!(explanation.frequency, frequency, "{}", word);
+            assert_eq!(explanation.min_ratio, 0.7);
+        }
+        assert_eq!(
+            classify_explain(&speller, "TOMATOx").to_string(),
+            "rule=partial-match substring=\"TOMATO\" range=0..6 ratio=0.86 frequency=2 min_ratio=0.70 min_substring_len=1"
+        );
+        assert_eq!(classify_explain(&speller, "xyzzy").to_string(), "rule=no-match min_ratio=0.70 min_substring_len=1");
+
+        let config = ClassifierConfig { ignore: vec!["LOREM".to_string()], min_word_len: 3, min_substring_len: 7, ..ClassifierConfig::default() };
+        assert_eq!(classify_explain_with_config(&speller, "lorem", &config).rule, Rule::Ignored);
+        assert_eq!(classify_explain_with_config(&speller, "ad", &config).rule, Rule::TooShort);
+        // "tomato" is too short a substring to count any more.
+        let explanation = classify_explain_with_config(&speller, "TOMATOx", &config);
+        assert_eq!((explanation.rule, explanation.status), (Rule::NoMatch, IpsumStatus::NotLorem));
+        assert_eq!(explanation.min_substring_len, 7);
+    }
+
+    #[test]
+    fn test_classify_misspelled_filler() {
+        let speller = train_speller();
+        let classifier = IpsumClassifier::new(&speller);
+        let options = FuzzyOptions::default();
+        for word in ["lorm", "ipsm", "dolr", "consecteur", "tomato", "edit", "tomatox", "lorem"] {
+            assert_eq!(classifier.classify_fuzzy(word, &options), classify_word_fuzzy(&speller, word, &options), "{}", word);
+        }
+        assert_eq!(classify_word(&speller, "dolr"), IpsumStatus::NotLorem);
+        assert_eq!(classify_word_fuzzy(&speller, "dolr", &options), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word_fuzzy(&speller, "consecteur", &options), IpsumStatus::FullyIpsum);
+        assert_eq!(classify_word_fuzzy(&speller, "edit", &options), IpsumStatus::NotLorem);
+        assert_eq!(classify_word_fuzzy(&speller, "tomato", &options), IpsumStatus::NotLorem);
+
+        let short = ClassifierConfig { min_word_len: 5, ..ClassifierConfig::default() };
+        let ignoring = ClassifierConfig { ignore: vec!["Dolr".to_string()], ..ClassifierConfig::default() };
+        let case_sensitive = ClassifierConfig { case_sensitive: true, ..ClassifierConfig::default() };
+        let cases = [
+            (&short, "dolr", IpsumStatus::NotLorem),
+            (&short, "consecteur", IpsumStatus::FullyIpsum),
+            (&ignoring, "dolr", IpsumStatus::NotLorem),
+            (&case_sensitive, "dolr", IpsumStatus::FullyIpsum),
+            // "Lorm" is two edits from "lorem" when case matters, too many for a short word.
+            (&case_sensitive, "Lorm", IpsumStatus::NotLorem),
+            (&ClassifierConfig::default(), "Lorm", IpsumStatus::FullyIpsum),
+        ];
+        for (config, word, status) in cases {
+            assert_eq!(classify_word_fuzzy_with_config(&speller, word, &options, config), status, "{}", word);
+            let classifier = IpsumClassifier::new(&speller).with_config(config.clone());
+            assert_eq!(classifier.classify_fuzzy(word, &options), status, "{}", word);
         }
     }
 }
@@ -2,18 +2,34 @@
 pub mod lorem;
 pub mod ipsum;
 pub mod dolor;
+pub mod classifier;
+pub mod config;
+pub mod english;
+pub mod context;
+pub mod detector;
+pub mod fuzzy;
+pub mod greeking;
+pub mod hmm;
 pub mod ipsum_classifier;
 pub mod markov_amet;
+pub mod matcher;
+pub mod model;
+pub mod pseudonym;
+pub mod scan;
+pub mod segment;
+pub mod stego;
+pub mod structure;
+pub mod tokenizer;
+pub mod watermark;
 
 #[cfg(test)]
 mod spellcheck_tests {
-    use super::*; // Import functions from the current module
     use spellcheck::Speller;
     use std::collections::HashMap;
 
     /// Helper function: creates a new Speller and trains it on a simple sentence.
     fn train_speller() -> Speller {
-        let mut n_words = HashMap::new();
+        let n_words = HashMap::new();
         let mut speller = Speller {
             letters: "abcdefghijklmnopqrstuvwxyz".to_string(),
             n_words,
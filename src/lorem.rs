@@ -5,49 +5,60 @@
 //!
 //! Note: This is a toy example and not a replacement for a full NLP model.
 
-use rand::prelude::{IteratorRandom, SliceRandom};
-use rand::prelude::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use std::collections::{HashMap, HashSet};
 
+use crate::config::ClassifierConfig;
+use crate::model::{GenerateError, MarkovModel};
+use crate::tokenizer;
+
+/// Creates a deterministic random number generator from a `u64` seed.
+///
+/// Passing the returned generator to any of the `*_with_rng` functions produces
+/// byte-identical output for the same seed and model, which makes it suitable for
+/// snapshot tests and fixture builds.
+pub fn seeded_rng(rng_seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(rng_seed)
+}
+
 /// Builds a simple Markov chain model from the given seed text.
 /// The model maps each word (in lowercase) to a vector of possible subsequent words.
+///
+/// This expands a first-order [`MarkovModel`], which stores the same transitions as
+/// interned tokens with counts; prefer the model directly for large seeds.
 pub fn build_markov_chain(seed: &str) -> HashMap<String, Vec<String>> {
-    // Explicit type annotation helps the compiler infer that the values are Vec<String>
-    let mut chain: HashMap<String, Vec<String>> = HashMap::new();
-    let words: Vec<&str> = seed.split_whitespace().collect();
-    for window in words.windows(2) {
-        if let [first, second] = window {
-            let key = first.to_lowercase();
-            chain.entry(key).or_default().push(second.to_string());
-        }
-    }
-    chain
+    MarkovModel::train(seed, 1).to_chain()
 }
 
 /// Generates text using the provided Markov chain model.
 /// It starts with a random word from the model's keys and continues for `num_words` words.
-pub fn generate_markov_text(chain: &HashMap<String, Vec<String>>, num_words: usize) -> String {
-    let mut rng = rand::rng();
-    // Use the SliceRandom trait so that choose() is available.
-    let start = chain.keys().choose(&mut rng).unwrap().to_string();
-    let mut result = vec![start.clone()];
-    let mut current = start;
-    for _ in 1..num_words {
-        if let Some(choices) = chain.get(&current) {
-            // `choose` is provided by SliceRandom.
-            current = choices.choose(&mut rng).unwrap().clone();
-            result.push(current.clone());
-        } else {
-            break;
-        }
-    }
-    result.join(" ")
+pub fn generate_markov_text(chain: &HashMap<String, Vec<String>>, num_words: usize) -> Result<String, GenerateError> {
+    generate_markov_text_with_rng(chain, num_words, &mut rand::rng())
+}
+
+/// Generates text using the provided Markov chain model and a caller-supplied RNG.
+/// The keys are visited in sorted order so the output only depends on the chain and the RNG state.
+pub fn generate_markov_text_with_rng<R: Rng + ?Sized>(
+    chain: &HashMap<String, Vec<String>>,
+    num_words: usize,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    MarkovModel::from_chain(chain).generate_with_rng(num_words, rng)
 }
 
 /// Generates a Lorem Ipsumlike sample text using the given seed and desired length (in words).
-pub fn generate_lorem_sample(seed: &str, sample_length: usize) -> String {
-    let chain = build_markov_chain(seed);
-    generate_markov_text(&chain, sample_length)
+pub fn generate_lorem_sample(seed: &str, sample_length: usize) -> Result<String, GenerateError> {
+    generate_lorem_sample_with_rng(seed, sample_length, &mut rand::rng())
+}
+
+/// Generates a Lorem Ipsumlike sample text from the given seed using a caller-supplied RNG.
+pub fn generate_lorem_sample_with_rng<R: Rng + ?Sized>(
+    seed: &str,
+    sample_length: usize,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    MarkovModel::train(seed, 1).generate_with_rng(sample_length, rng)
 }
 
 /// Computes the Jaccard similarity between two sets of words.
@@ -64,27 +75,71 @@ pub fn jaccard_similarity(set_a: &HashSet<String>, set_b: &HashSet<String>) -> f
 
 /// Detects whether the input text appears to be Lorem Ipsumlike.
 /// The function generates a sample from the given seed, converts both the generated sample
-/// and the input text into word sets (case-insensitive), and then calculates their Jaccard similarity.
-/// If the similarity is greater than 0.3, the text is flagged as Lorem Ipsumlike.
+/// and the input text into sets of words (case-insensitive, without punctuation; see [`tokenizer::words`]),
+/// and then calculates their Jaccard similarity.
+/// If the similarity is greater than 0.3 (see [`ClassifierConfig::jaccard_threshold`]), the text
+/// is flagged as Lorem Ipsumlike.
+/// The verdict depends on the random sample; see [`crate::detector::LoremDetector`] for a
+/// deterministic detector that reports a score.
 pub fn detect_lorem_ipsum(text: &str, seed: &str, sample_length: usize) -> bool {
-    let sample = generate_lorem_sample(seed, sample_length);
-    let sample_set: HashSet<String> = sample
-        .split_whitespace()
-        .map(|w| w.to_lowercase())
-        .collect();
-    let text_set: HashSet<String> = text
-        .split_whitespace()
-        .map(|w| w.to_lowercase())
+    detect_lorem_ipsum_with_rng(text, seed, sample_length, &mut rand::rng())
+}
+
+/// Detects whether the input text appears to be Lorem Ipsumlike, generating the
+/// comparison sample with a caller-supplied RNG so the verdict is reproducible.
+pub fn detect_lorem_ipsum_with_rng<R: Rng + ?Sized>(text: &str, seed: &str, sample_length: usize, rng: &mut R) -> bool {
+    detect_lorem_ipsum_with_model(text, &MarkovModel::train(seed, 1), sample_length, rng)
+}
+
+/// Detects whether the input text appears to be Lorem Ipsumlike, drawing the comparison
+/// sample from an already trained (or loaded) model instead of rebuilding it from a seed.
+pub fn detect_lorem_ipsum_with_model<R: Rng + ?Sized>(
+    text: &str,
+    model: &MarkovModel,
+    sample_length: usize,
+    rng: &mut R,
+) -> bool {
+    detect_lorem_ipsum_with_config(text, model, sample_length, &ClassifierConfig::default(), rng)
+}
+
+/// [`detect_lorem_ipsum_with_model`] with the Jaccard threshold and case handling of `config`.
+/// Words of the text that `config` skips are left out of the comparison.
+pub fn detect_lorem_ipsum_with_config<R: Rng + ?Sized>(
+    text: &str,
+    model: &MarkovModel,
+    sample_length: usize,
+    config: &ClassifierConfig,
+    rng: &mut R,
+) -> bool {
+    let fold = |word: &str| if config.case_sensitive { word.to_string() } else { word.to_lowercase() };
+    // A model with nothing to generate shares no words with the text.
+    let sample = model.generate_with_rng(sample_length, rng).unwrap_or_default();
+    let sample_set: HashSet<String> = tokenizer::words(&sample).map(|w| fold(w.text)).collect();
+    let text_set: HashSet<String> = tokenizer::words(text)
+        .filter(|w| !config.skips(w.text))
+        .map(|w| fold(w.text))
         .collect();
-    jaccard_similarity(&sample_set, &text_set) > 0.3
+    jaccard_similarity(&sample_set, &text_set) > config.jaccard_threshold
 }
 
 /// A generator function that builds the model and generates a sample.
 /// Returns both the Markov chain model and the generated sample.
-pub fn generate_lorem_model(seed: &str, sample_length: usize) -> (HashMap<String, Vec<String>>, String) {
-    let chain = build_markov_chain(seed);
-    let sample = generate_markov_text(&chain, sample_length);
-    (chain, sample)
+/// The chain is expanded from a first-order [`MarkovModel`]; train one directly to keep the
+/// compact form, for example to [save](MarkovModel::save) it.
+pub fn generate_lorem_model(seed: &str, sample_length: usize) -> Result<(HashMap<String, Vec<String>>, String), GenerateError> {
+    generate_lorem_model_with_rng(seed, sample_length, &mut rand::rng())
+}
+
+/// Builds the model and generates a sample using a caller-supplied RNG.
+/// Returns both the Markov chain model and the generated sample.
+pub fn generate_lorem_model_with_rng<R: Rng + ?Sized>(
+    seed: &str,
+    sample_length: usize,
+    rng: &mut R,
+) -> Result<(HashMap<String, Vec<String>>, String), GenerateError> {
+    let model = MarkovModel::train(seed, 1);
+    let sample = model.generate_with_rng(sample_length, rng)?;
+    Ok((model.to_chain(), sample))
 }
 
 #[cfg(test)]
@@ -94,7 +149,7 @@ mod tests {
     #[test]
     fn test_generate_lorem_sample() {
         let seed = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
-        let sample = generate_lorem_sample(seed, 20);
+        let sample = generate_lorem_sample(seed, 20).unwrap();
         println!("Generated sample: {}", sample);
         assert!(!sample.is_empty());
     }
@@ -103,9 +158,51 @@ mod tests {
     fn test_detect_lorem_ipsum() {
         let seed = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
         let test_text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
-        let result = detect_lorem_ipsum(test_text, seed, 30);
+        // RNG seed 19 generates a sample that starts at the top of the passage.
+        let result = detect_lorem_ipsum_with_rng(test_text, seed, 30, &mut seeded_rng(19));
         // Since test_text is similar to the seed, we expect a positive detection.
         assert!(result);
     }
-}
 
+    #[test]
+    fn test_detect_lorem_ipsum_with_config() {
+        let model = MarkovModel::train("Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.", 1);
+        let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+        let detect = |config: &ClassifierConfig| detect_lorem_ipsum_with_config(text, &model, 30, config, &mut seeded_rng(19));
+        assert!(detect(&ClassifierConfig::default()));
+        assert!(!detect(&ClassifierConfig { jaccard_threshold: 1.0, ..ClassifierConfig::default() }));
+        // Ignoring most of the text leaves too little to compare.
+        let ignore = ["lorem", "ipsum", "dolor", "sit", "amet", "consectetur"].map(String::from).to_vec();
+        assert!(!detect(&ClassifierConfig { ignore, ..ClassifierConfig::default() }));
+    }
+
+    const PASSAGE: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        let first = generate_lorem_sample_with_rng(PASSAGE, 40, &mut seeded_rng(1234)).unwrap();
+        let second = generate_lorem_sample_with_rng(PASSAGE, 40, &mut seeded_rng(1234)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_seeded_generation_golden() {
+        let sample = generate_lorem_sample_with_rng(PASSAGE, 20, &mut seeded_rng(3)).unwrap();
+        assert_eq!(
+            sample,
+            "adipiscing elit, sed do eiusmod tempor incididunt ut aliquip ex ea commodo consequat. Duis aute irure dolor sit amet, consectetur"
+        );
+        let (chain, model_sample) = generate_lorem_model_with_rng(PASSAGE, 20, &mut seeded_rng(3)).unwrap();
+        assert_eq!(chain, build_markov_chain(PASSAGE));
+        assert_eq!(model_sample, sample);
+    }
+
+    #[test]
+    fn test_generate_exact_length_and_empty_chain() {
+        let seed = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+        let sample = generate_lorem_sample_with_rng(seed, 50, &mut seeded_rng(0)).unwrap();
+        assert_eq!(sample.split_whitespace().count(), 50);
+        assert_eq!(generate_markov_text(&HashMap::new(), 10), Err(GenerateError::EmptyModel));
+        assert_eq!(generate_lorem_sample("lorem", 10), Err(GenerateError::EmptyModel));
+    }
+}
@@ -1,6 +1,22 @@
-use clap::{Parser, Subcommand};
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use anyhow::{Context, Result};
+use spellcheck::Speller;
 use lorem_ipsumlike::lorem;
+use lorem_ipsumlike::classifier::{All, Any, Classifier, CorpusClassifier, DictionaryClassifier, WeightedVote};
+use lorem_ipsumlike::config::ClassifierConfig;
+use lorem_ipsumlike::context::ContextClassifier;
+use lorem_ipsumlike::detector::{LoremDetector, SpanGranularity};
+use lorem_ipsumlike::scan::{self, ScanOptions};
+use lorem_ipsumlike::english;
+use lorem_ipsumlike::greeking;
+use lorem_ipsumlike::ipsum;
+use lorem_ipsumlike::ipsum_classifier::IpsumClassifier;
+use lorem_ipsumlike::matcher::WordMatcher;
+use lorem_ipsumlike::model::{MarkovModel, ModelFormat};
+use lorem_ipsumlike::structure::{self, TextShape, TextUnit};
+use std::ops::RangeInclusive;
 
 #[derive(Parser, Debug)]
 #[command(name = "lorem_tool", version = "0.1.0", about = "Generate and detect Lorem Ipsum-like text", long_about = None)]
@@ -16,9 +32,27 @@ enum Commands {
         /// Seed text for generating lorem ipsum (optional).
         #[arg(short, long, default_value = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.")]
         seed: String,
-        /// Number of words to generate.
+        /// Load a trained model (see `train`) instead of building one from --seed.
+        #[arg(short, long, conflicts_with_all = ["seed", "order"])]
+        model: Option<PathBuf>,
+        /// Number of words, sentences or paragraphs to generate (see --unit).
         #[arg(short, long, default_value_t = 50)]
         length: usize,
+        /// Whether --length counts words, sentences or paragraphs.
+        #[arg(short, long, value_enum, default_value_t = UnitArg::Words)]
+        unit: UnitArg,
+        /// Words per sentence, as `N` or `MIN-MAX`.
+        #[arg(long, default_value = "6-14", value_parser = parse_range)]
+        sentence_words: RangeInclusive<usize>,
+        /// Sentences per paragraph, as `N` or `MIN-MAX`.
+        #[arg(long, default_value = "3-6", value_parser = parse_range)]
+        paragraph_sentences: RangeInclusive<usize>,
+        /// Number of previous words each generated word depends on (1 = bigram, 2 = trigram, ...).
+        #[arg(short, long, default_value_t = 1)]
+        order: usize,
+        /// Seed for the random number generator; the same value always produces the same text.
+        #[arg(long)]
+        rng_seed: Option<u64>,
     },
     /// Detect if the given text is Lorem Ipsum-like.
     Det {
@@ -27,10 +61,268 @@ enum Commands {
         /// Seed text for generating a sample (optional).
         #[arg(short, long, default_value = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.")]
         seed: String,
-        /// Number of words for the generated sample.
-        #[arg(short, long, default_value_t = 50)]
-        length: usize,
+        /// Load a trained model (see `train`) instead of building one from --seed.
+        #[arg(short, long, conflicts_with = "seed")]
+        model: Option<PathBuf>,
+        /// Score at or above which the text is reported as Lorem Ipsum-like.
+        #[arg(short, long, default_value_t = LoremDetector::DEFAULT_THRESHOLD)]
+        threshold: f64,
+        /// Instead of scoring the text, compare its words with a generated sample of this many
+        /// words; the text is flagged when their Jaccard similarity is above --jaccard-threshold.
+        #[arg(long, value_name = "WORDS")]
+        sample: Option<usize>,
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Scan a directory tree for leftover placeholder text; exits with status 1 if any is found
+    /// and 2 if the scan failed or some files could not be read.
+    Scan {
+        /// The file or directory to scan. Files matched by .gitignore are skipped.
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Seed text for the lorem side of the detector (optional).
+        #[arg(short, long, default_value = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.")]
+        seed: String,
+        /// Load a trained model (see `train`) instead of building one from --seed.
+        #[arg(short, long, conflicts_with = "seed")]
+        model: Option<PathBuf>,
+        /// Score at or above which a span is reported as filler.
+        #[arg(short, long, default_value_t = LoremDetector::DEFAULT_THRESHOLD)]
+        threshold: f64,
+        /// Score windows of this many words instead of whole sentences.
+        #[arg(short, long)]
+        window: Option<usize>,
+        /// Do not report spans with fewer words than this.
+        #[arg(long, default_value_t = ScanOptions::default().min_words)]
+        min_words: usize,
+        #[command(flatten)]
+        config: ConfigArgs,
     },
+    /// Replace every word of a text with filler of the same length and case, keeping the layout.
+    Greek {
+        /// The file to greek; reads standard input if omitted.
+        input: Option<PathBuf>,
+        /// Seed for the random number generator; the same value always produces the same text.
+        #[arg(long)]
+        rng_seed: Option<u64>,
+    },
+    /// Remove filler words from a text, keeping its layout.
+    Strip {
+        /// The file to clean; reads standard input if omitted.
+        input: Option<PathBuf>,
+        #[command(flatten)]
+        classifier: ClassifierArgs,
+    },
+    /// Print every word of a text with its classification.
+    Classify {
+        /// The file to classify; reads standard input if omitted.
+        input: Option<PathBuf>,
+        #[command(flatten)]
+        classifier: ClassifierArgs,
+        /// Also print why the classifier decided each word as it did: the rule that fired, the
+        /// longest dictionary or corpus substring, its ratio and frequency, and the threshold.
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Train a Markov model and save it for use with --model.
+    Train {
+        /// Seed text to train on.
+        #[arg(short, long, required_unless_present = "input", conflicts_with = "input")]
+        seed: Option<String>,
+        /// Read the training text from a file instead of --seed.
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Number of previous words each generated word depends on (1 = bigram, 2 = trigram, ...).
+        #[arg(long, default_value_t = 1)]
+        order: usize,
+        /// Where to write the model.
+        #[arg(short, long)]
+        output: PathBuf,
+        /// File format; defaults to JSON for `.json` paths and binary otherwise.
+        #[arg(short, long, value_enum)]
+        format: Option<FormatArg>,
+    },
+}
+
+/// How `strip` and `classify` decide which words are filler.
+#[derive(Args, Debug)]
+struct ClassifierArgs {
+    /// The classifiers to use; give several, separated by commas, to combine them.
+    #[arg(short, long, value_enum, value_delimiter = ',', default_values_t = [ClassifierArg::Context])]
+    classifier: Vec<ClassifierArg>,
+    /// How the verdicts of several classifiers are combined.
+    #[arg(long, value_enum, default_value_t = CombineArg::Any)]
+    combine: CombineArg,
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+/// The thresholds, word lists and case handling shared by the subcommands that classify words.
+#[derive(Args, Debug)]
+struct ConfigArgs {
+    /// Read thresholds and word lists from this TOML file; the options below override it.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Share of a word its longest dictionary substring must cover for the word to be partly real.
+    #[arg(long)]
+    min_partial_ratio: Option<f64>,
+    /// Jaccard similarity above which `det --sample` flags a text.
+    #[arg(long)]
+    jaccard_threshold: Option<f64>,
+    /// Ignore dictionary and corpus substrings shorter than this many characters.
+    #[arg(long)]
+    min_substring_len: Option<usize>,
+    /// Treat words shorter than this many characters as real.
+    #[arg(long)]
+    min_word_len: Option<usize>,
+    /// Match the corpus and dictionary case-sensitively.
+    #[arg(long)]
+    case_sensitive: bool,
+    /// Words that are never filler, separated by commas; added to the configuration's list.
+    #[arg(long, value_delimiter = ',')]
+    ignore: Vec<String>,
+}
+
+/// The classifiers accepted by `--classifier`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ClassifierArg {
+    /// Words the English dictionary does not know are filler.
+    Dictionary,
+    /// Corpus words are filler, then the dictionary and substring rules decide.
+    Rules,
+    /// Only corpus words, and words that are mostly a corpus word, are filler.
+    Corpus,
+    /// Like `rules`, but words shared by Latin and English are decided by their neighbours.
+    Context,
+}
+
+/// The combinations accepted by `--combine`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CombineArg {
+    /// Filler if any classifier says so.
+    Any,
+    /// Filler if every classifier says so.
+    All,
+    /// Filler if most classifiers say so.
+    Vote,
+}
+
+impl ConfigArgs {
+    /// The configuration from --config, with the other options applied on top.
+    fn load(&self) -> Result<ClassifierConfig> {
+        let mut config = match &self.config {
+            Some(path) => ClassifierConfig::load(path)
+                .with_context(|| format!("failed to load configuration from {}", path.display()))?,
+            None => ClassifierConfig::default(),
+        };
+        if let Some(ratio) = self.min_partial_ratio {
+            config.min_partial_ratio = ratio;
+        }
+        if let Some(threshold) = self.jaccard_threshold {
+            config.jaccard_threshold = threshold;
+        }
+        if let Some(len) = self.min_substring_len {
+            config.min_substring_len = len;
+        }
+        if let Some(len) = self.min_word_len {
+            config.min_word_len = len;
+        }
+        config.case_sensitive |= self.case_sensitive;
+        config.ignore.extend(self.ignore.iter().cloned());
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl ClassifierArgs {
+    /// Builds the classifier over the dictionary of `speller`.
+    fn build<'a>(&self, speller: &'a Speller, config: &ClassifierConfig) -> Box<dyn Classifier + 'a> {
+        let member = |kind: ClassifierArg| -> Box<dyn Classifier + 'a> {
+            let config = config.clone();
+            match kind {
+                ClassifierArg::Dictionary => Box::new(DictionaryClassifier::new(speller).with_config(config)),
+                ClassifierArg::Rules => Box::new(IpsumClassifier::new(speller).with_config(config)),
+                ClassifierArg::Corpus => Box::new(CorpusClassifier::new().with_config(config)),
+                ClassifierArg::Context => Box::new(ContextClassifier::new(speller).with_config(config)),
+            }
+        };
+        if let [kind] = self.classifier[..] {
+            return member(kind);
+        }
+        let kinds = self.classifier.iter().copied();
+        match self.combine {
+            CombineArg::Any => Box::new(kinds.fold(Any::new(), |any, kind| any.with(member(kind)))),
+            CombineArg::All => Box::new(kinds.fold(All::new(), |all, kind| all.with(member(kind)))),
+            CombineArg::Vote => Box::new(kinds.fold(WeightedVote::new(), |vote, kind| vote.with(member(kind), 1.0))),
+        }
+    }
+}
+
+/// The model file formats accepted by `train --format`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum FormatArg {
+    Binary,
+    Json,
+}
+
+impl From<FormatArg> for ModelFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Binary => ModelFormat::Binary,
+            FormatArg::Json => ModelFormat::Json,
+        }
+    }
+}
+
+/// The output units accepted by `gen --unit`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum UnitArg {
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+impl From<UnitArg> for TextUnit {
+    fn from(unit: UnitArg) -> Self {
+        match unit {
+            UnitArg::Words => TextUnit::Words,
+            UnitArg::Sentences => TextUnit::Sentences,
+            UnitArg::Paragraphs => TextUnit::Paragraphs,
+        }
+    }
+}
+
+/// Parses a length range written as `N` or `MIN-MAX`.
+fn parse_range(value: &str) -> Result<RangeInclusive<usize>, String> {
+    let parse = |part: &str| part.trim().parse::<usize>().map_err(|e| format!("invalid length {:?}: {}", part, e));
+    let (min, max) = match value.split_once('-') {
+        Some((min, max)) => (parse(min)?, parse(max)?),
+        None => {
+            let n = parse(value)?;
+            (n, n)
+        }
+    };
+    if min == 0 || min > max {
+        return Err(format!("expected 1 <= MIN <= MAX, got {}", value));
+    }
+    Ok(min..=max)
+}
+
+/// Loads the model at `path` if one was given, otherwise trains one on `seed`.
+fn load_or_train(path: Option<&Path>, seed: &str, order: usize) -> Result<MarkovModel> {
+    match path {
+        Some(path) => MarkovModel::load(path)
+            .with_context(|| format!("failed to load model from {}", path.display())),
+        None => Ok(MarkovModel::train(seed, order)),
+    }
+}
+
+/// Reads the file at `path`, or standard input if there is none.
+fn read_input(path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) => std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display())),
+        None => std::io::read_to_string(std::io::stdin()).context("failed to read standard input"),
+    }
 }
 
 #[tokio::main]
@@ -38,13 +330,117 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Gen { seed, length } => {
-            let generated = lorem::generate_lorem_sample(seed, *length);
+        Commands::Gen { seed, model, length, unit, sentence_words, paragraph_sentences, order, rng_seed } => {
+            let model = load_or_train(model.as_deref(), seed, *order)?;
+            let shape = TextShape {
+                sentence_words: sentence_words.clone(),
+                paragraph_sentences: paragraph_sentences.clone(),
+                ..TextShape::default()
+            };
+            let generated = match rng_seed {
+                Some(rng_seed) => structure::generate_text_with_rng(&model, (*unit).into(), *length, &shape, &mut lorem::seeded_rng(*rng_seed)),
+                None => structure::generate_text_with_rng(&model, (*unit).into(), *length, &shape, &mut rand::rng()),
+            }?;
             println!("Generated Lorem Ipsum-like text:\n{}", generated);
         },
-        Commands::Det { text, seed, length } => {
-            let is_lorem = lorem::detect_lorem_ipsum(text, seed, *length);
-            println!("Text is {}Lorem Ipsum-like", if is_lorem { "" } else { "NOT " });
+        Commands::Det { text, seed, model, threshold, sample, config } => {
+            let model = load_or_train(model.as_deref(), seed, 1)?;
+            let config = config.load()?;
+            if let Some(sample) = sample {
+                let is_lorem = lorem::detect_lorem_ipsum_with_config(text, &model, *sample, &config, &mut rand::rng());
+                println!(
+                    "Text is {}Lorem Ipsum-like (Jaccard threshold {:.2})",
+                    if is_lorem { "" } else { "NOT " },
+                    config.jaccard_threshold,
+                );
+                return Ok(());
+            }
+            let detection = LoremDetector::from_model(&model).with_threshold(*threshold).with_config(config).detect(text);
+            println!(
+                "Text is {}Lorem Ipsum-like (score {:.2}, threshold {:.2})",
+                if detection.is_lorem { "" } else { "NOT " },
+                detection.score,
+                threshold,
+            );
+        },
+        Commands::Scan { path, seed, model, threshold, window, min_words, config } => {
+            // Status 1 is reserved for filler, so a scan that could not run exits with 2.
+            let report = match load_or_train(model.as_deref(), seed, 1).and_then(|model| Ok((model, config.load()?))) {
+                Ok((model, config)) => {
+                    let detector = LoremDetector::from_model(&model).with_threshold(*threshold).with_config(config);
+                    let options = ScanOptions {
+                        granularity: window.map_or(SpanGranularity::Sentences, SpanGranularity::Window),
+                        min_words: *min_words,
+                    };
+                    scan::scan_dir(&detector, &WordMatcher::from_speller(&english::speller()), path, &options)
+                }
+                Err(err) => {
+                    eprintln!("Error: {:?}", err);
+                    std::process::exit(2);
+                }
+            };
+            for finding in &report.findings {
+                println!("{}", finding);
+            }
+            if !report.findings.is_empty() {
+                eprintln!("{} filler span(s) found", report.findings.len());
+            }
+            let failed = report.errors.len();
+            for err in report.errors {
+                eprintln!("error: {:#}", anyhow::Error::from(err));
+            }
+            if failed > 0 {
+                eprintln!("{} path(s) could not be scanned", failed);
+                std::process::exit(2);
+            }
+            if !report.findings.is_empty() {
+                std::process::exit(1);
+            }
+        },
+        Commands::Greek { input, rng_seed } => {
+            let text = read_input(input.as_deref())?;
+            let greeked = match rng_seed {
+                Some(rng_seed) => greeking::greek_with_rng(&text, &mut lorem::seeded_rng(*rng_seed)),
+                None => greeking::greek(&text),
+            };
+            print!("{}", greeked);
+        },
+        Commands::Strip { input, classifier } => {
+            let text = read_input(input.as_deref())?;
+            let speller = english::speller();
+            let config = classifier.config.load()?;
+            print!("{}", ipsum::remove_ipsum(&classifier.build(&speller, &config), &text));
+        },
+        Commands::Classify { input, classifier, explain } => {
+            let text = read_input(input.as_deref())?;
+            let speller = english::speller();
+            let config = classifier.config.load()?;
+            let classifier = classifier.build(&speller, &config);
+            if *explain {
+                for (range, explanation) in classifier.explain_text(&text) {
+                    let word = &text[range.clone()];
+                    let explanation = explanation.with_frequency(&speller, word);
+                    println!("{}..{}\t{}\t{:?}\t{}", range.start, range.end, word, explanation.status, explanation);
+                }
+            } else {
+                for (range, status) in ipsum::scan_text(&classifier, &text) {
+                    println!("{}..{}\t{}\t{:?}", range.start, range.end, &text[range.clone()], status);
+                }
+            }
+        },
+        Commands::Train { seed, input, order, output, format } => {
+            let text = match (seed, input) {
+                (Some(seed), _) => seed.clone(),
+                (None, Some(input)) => std::fs::read_to_string(input)
+                    .with_context(|| format!("failed to read {}", input.display()))?,
+                (None, None) => unreachable!("clap requires --seed or --input"),
+            };
+            let model = MarkovModel::train(&text, *order);
+            let format = format.map(ModelFormat::from).unwrap_or_else(|| ModelFormat::from_path(output));
+            model
+                .save(output, format)
+                .with_context(|| format!("failed to save model to {}", output.display()))?;
+            println!("Saved order-{} model with {} words to {}", model.order(), model.vocab_len(), output.display());
         },
     }
     Ok(())
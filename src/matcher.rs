@@ -0,0 +1,228 @@
+//! Precompiled word matching for the classifiers.
+//!
+//! A [`WordMatcher`] is an Aho-Corasick automaton over a fixed set of words. It is built once
+//! and then answers both "is this a known word?" and "what is the longest known word embedded
+//! in this one?" in time linear in the length of the input, instead of looking up every
+//! substring in a `HashMap`.
+//!
+//! The automaton works on bytes. Because UTF-8 is self-synchronizing, a match of a whole
+//! UTF-8 word inside UTF-8 text always starts and ends on character boundaries, so the
+//! ranges it reports can be used to slice the input.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+
+use spellcheck::Speller;
+
+/// The root state of the automaton.
+const ROOT: u32 = 0;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Trie edges, sorted by byte.
+    next: Vec<(u8, u32)>,
+    /// The state for the longest proper suffix of this state's path that is also in the trie.
+    fail: u32,
+    /// Whether this state's path is itself one of the words.
+    terminal: bool,
+    /// The length of the longest word that is a suffix of this state's path, or 0.
+    longest: u32,
+}
+
+impl Node {
+    fn child(&self, byte: u8) -> Option<u32> {
+        self.next
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.next[i].1)
+    }
+}
+
+/// An Aho-Corasick automaton over a set of words.
+#[derive(Debug, Clone)]
+pub struct WordMatcher {
+    nodes: Vec<Node>,
+    words: usize,
+}
+
+impl WordMatcher {
+    /// Compiles `words` into a matcher. Empty words are ignored; duplicates are harmless.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut nodes = vec![Node::default()];
+        let mut count = 0;
+        for word in words {
+            let word = word.as_ref();
+            if word.is_empty() {
+                continue;
+            }
+            let mut state = ROOT;
+            for &byte in word.as_bytes() {
+                state = match nodes[state as usize].child(byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = nodes.len() as u32;
+                        nodes.push(Node::default());
+                        let edges = &mut nodes[state as usize].next;
+                        let at = edges.partition_point(|&(b, _)| b < byte);
+                        edges.insert(at, (byte, next));
+                        next
+                    }
+                };
+            }
+            let node = &mut nodes[state as usize];
+            if !node.terminal {
+                node.terminal = true;
+                node.longest = word.len() as u32;
+                count += 1;
+            }
+        }
+
+        // Breadth-first, so every failure link points at a state that is already finished.
+        let mut queue = VecDeque::from([ROOT]);
+        while let Some(state) = queue.pop_front() {
+            for (byte, child) in nodes[state as usize].next.clone() {
+                let fail = if state == ROOT {
+                    ROOT
+                } else {
+                    let mut fallback = nodes[state as usize].fail;
+                    loop {
+                        if let Some(next) = nodes[fallback as usize].child(byte) {
+                            break next;
+                        }
+                        if fallback == ROOT {
+                            break ROOT;
+                        }
+                        fallback = nodes[fallback as usize].fail;
+                    }
+                };
+                let inherited = nodes[fail as usize].longest;
+                let node = &mut nodes[child as usize];
+                node.fail = fail;
+                node.longest = node.longest.max(inherited);
+                queue.push_back(child);
+            }
+        }
+        WordMatcher { nodes, words: count }
+    }
+
+    /// Compiles the dictionary of a trained [`Speller`].
+    pub fn from_speller(speller: &Speller) -> Self {
+        // Sorting keeps the state numbering independent of HashMap iteration order.
+        let mut words: Vec<&String> = speller.n_words.keys().collect();
+        words.sort_unstable();
+        WordMatcher::new(words)
+    }
+
+    /// The number of distinct words in the matcher.
+    pub fn len(&self) -> usize {
+        self.words
+    }
+
+    /// Whether the matcher has no words.
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    /// Whether `word` is exactly one of the matcher's words.
+    pub fn contains(&self, word: &str) -> bool {
+        let mut state = ROOT;
+        for &byte in word.as_bytes() {
+            match self.nodes[state as usize].child(byte) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.nodes[state as usize].terminal
+    }
+
+    /// Finds the longest of the matcher's words that occurs in `text`, returning its byte range.
+    /// When several are equally long, the leftmost wins.
+    pub fn longest_match(&self, text: &str) -> Option<Range<usize>> {
+        let mut best: Option<Range<usize>> = None;
+        let mut state = ROOT;
+        for (i, &byte) in text.as_bytes().iter().enumerate() {
+            state = self.step(state, byte);
+            let len = self.nodes[state as usize].longest as usize;
+            if len > best.as_ref().map_or(0, |b| b.len()) {
+                best = Some(i + 1 - len..i + 1);
+            }
+        }
+        best
+    }
+
+    fn step(&self, mut state: u32, byte: u8) -> u32 {
+        loop {
+            if let Some(next) = self.nodes[state as usize].child(byte) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.nodes[state as usize].fail;
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for WordMatcher {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> Self {
+        WordMatcher::new(words)
+    }
+}
+
+/// The longest substring of `word` that is a key of `words`, found by looking up every substring.
+/// This is what the matcher replaces; it remains for one-off classifications where compiling
+/// the dictionary would cost more than it saves.
+pub(crate) fn longest_known_substring(words: &HashMap<String, u32>, word: &str) -> Option<Range<usize>> {
+    let mut best: Option<Range<usize>> = None;
+    for i in 0..word.len() {
+        for j in (i + 1)..=word.len() {
+            let Some(sub) = word.get(i..j) else {
+                continue;
+            };
+            if words.contains_key(sub) && j - i > best.as_ref().map_or(0, |b| b.len()) {
+                best = Some(i..j);
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let matcher = WordMatcher::new(["lorem", "ipsum", "lo", "", "ipsum"]);
+        assert_eq!(matcher.len(), 3);
+        assert!(matcher.contains("lorem") && matcher.contains("lo"));
+        assert!(!matcher.contains("lor") && !matcher.contains("loremx") && !matcher.contains(""));
+        assert!(WordMatcher::new(Vec::<&str>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let matcher: WordMatcher = ["he", "she", "his", "hers", "tomato", "mat"].into_iter().collect();
+        assert_eq!(matcher.longest_match("ushers"), Some(2..6));
+        assert_eq!(matcher.longest_match("xtomatox"), Some(1..7));
+        assert_eq!(matcher.longest_match("hishe"), Some(0..3));
+        assert_eq!(matcher.longest_match("xyz"), None);
+        assert_eq!(matcher.longest_match("straße-she"), Some(8..11));
+    }
+
+    #[test]
+    fn test_agrees_with_substring_search() {
+        let words: HashMap<String, u32> = ["a", "ab", "bab", "abcab", "c", "cabc", "ba"]
+            .iter()
+            .map(|w| (w.to_string(), 1))
+            .collect();
+        let matcher = WordMatcher::new(words.keys());
+        for text in ["", "abcabcab", "bababa", "ccc", "xabcabx", "cabcab", "dd"] {
+            assert_eq!(matcher.longest_match(text), longest_known_substring(&words, text), "{}", text);
+        }
+    }
+}
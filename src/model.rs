@@ -0,0 +1,878 @@
+//! An order-N Markov chain model for generating Lorem Ipsum–like text.
+//!
+//! Where [`crate::lorem::build_markov_chain`] only keys on the single previous word, a
+//! [`MarkovModel`] keys on the last N words (bigram, trigram, ...). When a context was never
+//! seen during training the model backs off to shorter contexts, down to a single word.
+//! Higher orders make the output read more like the seed text, at the cost of variety.
+//!
+//! Tokens are interned once and each context stores its distinct successors with cumulative
+//! occurrence counts, so a seed of a few megabytes costs a few bytes per transition rather
+//! than an owned `String` per occurrence. Training counts successors in hash maps and builds
+//! the cumulative weights once at the end, so it stays linear in the seed however many
+//! distinct words follow a context. Sampling picks a successor by binary search over the
+//! cumulative weights.
+//!
+//! Trained models can be saved with [`MarkovModel::save`] and loaded back with
+//! [`MarkovModel::load`], either as compact binary or as human-readable JSON.
+
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The index of an interned word in a [`MarkovModel`]'s vocabulary.
+type TokenId = u32;
+
+/// Stores each distinct word once and hands out stable ids for it.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Interner {
+    words: Vec<String>,
+    ids: HashMap<String, TokenId>,
+}
+
+impl Interner {
+    /// The id of `word`, interning it if it is new, or `None` if the vocabulary is full.
+    fn intern(&mut self, word: &str) -> Option<TokenId> {
+        if let Some(&id) = self.ids.get(word) {
+            return Some(id);
+        }
+        let id = TokenId::try_from(self.words.len()).ok()?;
+        self.words.push(word.to_string());
+        self.ids.insert(word.to_string(), id);
+        Some(id)
+    }
+
+    fn get(&self, word: &str) -> Option<TokenId> {
+        self.ids.get(word).copied()
+    }
+
+    fn resolve(&self, id: TokenId) -> &str {
+        &self.words[id as usize]
+    }
+
+    fn len(&self) -> usize {
+        self.words.len()
+    }
+}
+
+/// The distinct successors of one context, with cumulative occurrence counts.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Transitions {
+    /// Successors in order of first appearance.
+    tokens: Vec<TokenId>,
+    /// `cumulative[i]` is the total count of `tokens[..=i]`.
+    cumulative: Vec<u32>,
+}
+
+impl Transitions {
+    /// Builds the cumulative table from successors and their own counts, or returns `None`
+    /// if the counts add up to more than `u32::MAX`.
+    fn from_counts(counts: impl IntoIterator<Item = (TokenId, u32)>) -> Option<Self> {
+        let mut transitions = Transitions::default();
+        let mut total = 0u32;
+        for (token, count) in counts {
+            total = total.checked_add(count)?;
+            transitions.tokens.push(token);
+            transitions.cumulative.push(total);
+        }
+        Some(transitions)
+    }
+
+    fn total(&self) -> u32 {
+        self.cumulative.last().copied().unwrap_or(0)
+    }
+
+    /// Yields each successor with its own (non-cumulative) count.
+    fn weights(&self) -> impl Iterator<Item = (TokenId, u32)> + '_ {
+        let previous = std::iter::once(0).chain(self.cumulative.iter().copied());
+        self.tokens
+            .iter()
+            .zip(self.cumulative.iter().zip(previous))
+            .map(|(&token, (&c, p))| (token, c - p))
+    }
+
+    /// Picks a successor with probability proportional to its count.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<TokenId> {
+        if self.tokens.is_empty() {
+            return None;
+        }
+        let target = rng.random_range(0..self.total());
+        let i = self.cumulative.partition_point(|&c| c <= target);
+        Some(self.tokens[i])
+    }
+}
+
+/// Counts the successors of one context during training. Looking a successor up is a hash
+/// lookup however many there are; the cumulative [`Transitions`] are built once at the end.
+#[derive(Debug, Default)]
+struct TransitionCounts {
+    /// Successors in order of first appearance, with their counts.
+    counts: Vec<(TokenId, u32)>,
+    /// The position of each successor in `counts`.
+    index: HashMap<TokenId, usize>,
+}
+
+impl TransitionCounts {
+    fn add(&mut self, token: TokenId) {
+        match self.index.entry(token) {
+            Entry::Occupied(entry) => {
+                let count = &mut self.counts[*entry.get()].1;
+                *count = count.saturating_add(1);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(self.counts.len());
+                self.counts.push((token, 1));
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Builds the cumulative table. The total saturates at `u32::MAX`: the last successors
+    /// lose whatever count does not fit, and successors with none left are dropped.
+    fn into_transitions(self) -> Transitions {
+        let mut transitions = Transitions::default();
+        let mut total = 0u32;
+        for (token, count) in self.counts {
+            let count = count.min(u32::MAX - total);
+            if count == 0 {
+                break;
+            }
+            total += count;
+            transitions.tokens.push(token);
+            transitions.cumulative.push(total);
+        }
+        transitions
+    }
+}
+
+/// A Markov chain keyed on the last `order` words, with backoff to lower orders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkovModel {
+    order: usize,
+    /// Every surface form seen in training, plus its lowercased form.
+    vocab: Interner,
+    /// `lower[id]` is the id of the lowercased form of word `id`; contexts are keyed on these.
+    lower: Vec<TokenId>,
+    /// `tables[k]` maps a context of `k + 1` lowercased words to the words that followed it.
+    tables: Vec<HashMap<Vec<TokenId>, Transitions>>,
+    /// Runs of up to `order` words from the seed that generation may start from,
+    /// in order of first appearance.
+    starts: Vec<Vec<TokenId>>,
+    /// Indexes into `starts` of runs that open a sentence (their first word is capitalized).
+    /// Generation restarts from one of these after a dead end; derived, never serialized.
+    sentence_starts: Vec<usize>,
+}
+
+/// An error raised when a [`MarkovModel`] cannot generate text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerateError {
+    /// The model was trained on fewer than two words, so it has no transitions to follow.
+    EmptyModel,
+    /// The model generated nothing but punctuation, so there were no words to build a
+    /// sentence from.
+    NoWords,
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::EmptyModel => write!(f, "the model is empty; train it on at least two words"),
+            GenerateError::NoWords => write!(f, "the model generated only punctuation, no words"),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl MarkovModel {
+    fn empty(order: usize) -> Self {
+        MarkovModel {
+            order,
+            vocab: Interner::default(),
+            lower: Vec::new(),
+            tables: vec![HashMap::new(); order],
+            starts: Vec::new(),
+            sentence_starts: Vec::new(),
+        }
+    }
+
+    /// Recomputes `sentence_starts` after `starts` or `vocab` change.
+    fn index_sentence_starts(&mut self) {
+        self.sentence_starts = self
+            .starts
+            .iter()
+            .enumerate()
+            .filter(|(_, start)| {
+                let first = self.vocab.resolve(start[0]);
+                first.chars().next().is_some_and(char::is_uppercase)
+            })
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Picks a run to continue from after a dead end, preferring sentence starts.
+    fn restart<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&[TokenId]> {
+        let index = match self.sentence_starts.choose(rng) {
+            Some(&i) => i,
+            None => rng.random_range(0..self.starts.len().max(1)),
+        };
+        self.starts.get(index).map(|start| start.as_slice())
+    }
+
+    /// Interns a surface form and its lowercased form, keeping `lower` in step with `vocab`.
+    /// Returns `None` if the vocabulary has no room left for them.
+    fn intern(&mut self, word: &str) -> Option<TokenId> {
+        if let Some(id) = self.vocab.get(word) {
+            return Some(id);
+        }
+        let lowered = word.to_lowercase();
+        let lower_id = if lowered != word { Some(self.intern(&lowered)?) } else { None };
+        let id = self.vocab.intern(word)?;
+        self.lower.push(lower_id.unwrap_or(id));
+        Some(id)
+    }
+
+    /// Trains a model of the given order on the seed text.
+    /// An order of 0 is treated as 1. A seed with more distinct words than a `u32` can
+    /// number is only trained on up to the first word that does not fit.
+    pub fn train(seed: &str, order: usize) -> Self {
+        let order = order.max(1);
+        let mut model = MarkovModel::empty(order);
+        let words: Vec<TokenId> = seed.split_whitespace().map_while(|w| model.intern(w)).collect();
+        let lower: Vec<TokenId> = words.iter().map(|&id| model.lower[id as usize]).collect();
+
+        for (k, table) in model.tables.iter_mut().enumerate() {
+            let context_len = k + 1;
+            let mut counts: HashMap<&[TokenId], TransitionCounts> = HashMap::new();
+            for i in 0..words.len().saturating_sub(context_len) {
+                counts.entry(&lower[i..i + context_len]).or_default().add(words[i + context_len]);
+            }
+            *table = counts
+                .into_iter()
+                .map(|(context, counts)| (context.to_vec(), counts.into_transitions()))
+                .collect();
+        }
+
+        // Starting runs must have a successor, so they never include the final word.
+        let start_len = order.min(words.len().saturating_sub(1));
+        let mut seen = HashSet::new();
+        if start_len > 0 {
+            for i in 0..words.len() - start_len {
+                if seen.insert(&lower[i..i + start_len]) {
+                    model.starts.push(words[i..i + start_len].to_vec());
+                }
+            }
+        }
+
+        model.index_sentence_starts();
+        model
+    }
+
+    /// Builds a first-order model from a chain produced by [`crate::lorem::build_markov_chain`].
+    /// Generation may start from any key, visited in sorted order.
+    pub fn from_chain(chain: &HashMap<String, Vec<String>>) -> Self {
+        let mut model = MarkovModel::empty(1);
+        let mut keys: Vec<&String> = chain.keys().collect();
+        keys.sort();
+        for key in keys {
+            let Some(key_id) = model.intern(key) else { break };
+            let context = vec![model.lower[key_id as usize]];
+            let mut counts = TransitionCounts::default();
+            for next in &chain[key] {
+                let Some(next_id) = model.intern(next) else { break };
+                counts.add(next_id);
+            }
+            if !counts.is_empty() {
+                model.tables[0].insert(context, counts.into_transitions());
+                model.starts.push(vec![key_id]);
+            }
+        }
+        model.index_sentence_starts();
+        model
+    }
+
+    /// Expands the first-order transitions back into the chain format used by
+    /// [`crate::lorem::build_markov_chain`], repeating each successor by its count.
+    pub fn to_chain(&self) -> HashMap<String, Vec<String>> {
+        self.tables[0]
+            .iter()
+            .map(|(context, transitions)| {
+                let successors = transitions
+                    .weights()
+                    .flat_map(|(token, count)| {
+                        std::iter::repeat_n(self.vocab.resolve(token).to_string(), count as usize)
+                    })
+                    .collect();
+                (self.vocab.resolve(context[0]).to_string(), successors)
+            })
+            .collect()
+    }
+
+    /// The number of previous words the model keys on.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns true if the model was trained on fewer than two words and cannot generate text.
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// The number of distinct interned words, including lowercased forms.
+    pub fn vocab_len(&self) -> usize {
+        self.vocab.len()
+    }
+
+    /// Counts how often each lowercased word occurs in the training text, except for the
+    /// very first word, which never appears as a successor.
+    pub fn word_counts(&self) -> HashMap<String, u32> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for transitions in self.tables[0].values() {
+            for (token, count) in transitions.weights() {
+                let lower = self.vocab.resolve(self.lower[token as usize]);
+                *counts.entry(lower.to_string()).or_default() += count;
+            }
+        }
+        counts
+    }
+
+    /// Returns the successors of the longest suffix of `context` that was seen during training,
+    /// each paired with the number of times it followed that context.
+    ///
+    /// The context is matched case-insensitively. At most the last `order` words are used;
+    /// if that context is unseen the model backs off one word at a time.
+    pub fn successors<S: AsRef<str>>(&self, context: &[S]) -> Option<Vec<(&str, u32)>> {
+        let ids: Vec<Option<TokenId>> = context
+            .iter()
+            .map(|w| self.vocab.get(&w.as_ref().to_lowercase()))
+            .collect();
+        let longest = self.order.min(ids.len());
+        (1..=longest).rev().find_map(|k| {
+            let key: Option<Vec<TokenId>> = ids[ids.len() - k..].iter().copied().collect();
+            let transitions = self.tables[k - 1].get(&key?)?;
+            Some(
+                transitions
+                    .weights()
+                    .map(|(token, count)| (self.vocab.resolve(token), count))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Finds the transitions for the longest seen suffix of a generated run of tokens.
+    fn transitions_for(&self, result: &[TokenId], key: &mut Vec<TokenId>) -> Option<&Transitions> {
+        let longest = self.order.min(result.len());
+        (1..=longest).rev().find_map(|k| {
+            key.clear();
+            key.extend(result[result.len() - k..].iter().map(|&id| self.lower[id as usize]));
+            self.tables[k - 1].get(key.as_slice())
+        })
+    }
+
+    /// Generates `num_words` words of text using the thread-local RNG.
+    pub fn generate(&self, num_words: usize) -> Result<String, GenerateError> {
+        self.generate_with_rng(num_words, &mut rand::rng())
+    }
+
+    /// Generates exactly `num_words` words of text using a caller-supplied RNG.
+    ///
+    /// When the run reaches a word with no successors at any order (such as the last word
+    /// of the seed), generation continues from a fresh sentence start instead of stopping.
+    /// Returns [`GenerateError::EmptyModel`] if the model has nothing to generate from.
+    pub fn generate_with_rng<R: Rng + ?Sized>(&self, num_words: usize, rng: &mut R) -> Result<String, GenerateError> {
+        if num_words == 0 {
+            return Ok(String::new());
+        }
+        let start = self.starts.choose(rng).ok_or(GenerateError::EmptyModel)?;
+        let mut result: Vec<TokenId> = start.clone();
+        let mut key = Vec::with_capacity(self.order);
+        while result.len() < num_words {
+            match self.transitions_for(&result, &mut key).and_then(|t| t.sample(rng)) {
+                Some(next) => result.push(next),
+                None => {
+                    let restart = self.restart(rng).ok_or(GenerateError::EmptyModel)?;
+                    result.extend_from_slice(restart);
+                }
+            }
+        }
+        result.truncate(num_words);
+        Ok(result
+            .iter()
+            .map(|&id| self.vocab.resolve(id))
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+}
+
+/// The on-disk encodings supported by [`MarkovModel::save`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFormat {
+    /// Compact binary: [`MODEL_MAGIC`], the [`MODEL_FORMAT_VERSION`] as a little-endian
+    /// `u32`, then a bincode payload.
+    Binary,
+    /// Pretty-printed JSON, suitable for inspection and diffs.
+    Json,
+}
+
+impl ModelFormat {
+    /// Picks JSON for paths ending in `.json` and binary for everything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ModelFormat::Json,
+            _ => ModelFormat::Binary,
+        }
+    }
+}
+
+/// The bytes every binary model file starts with.
+pub const MODEL_MAGIC: &[u8; 8] = b"LIPSUMMK";
+
+/// The current model file version. Files with any other version are rejected on load, before
+/// their payload is decoded, so a file in a future layout reports
+/// [`ModelIoError::UnsupportedVersion`] rather than a decoding error.
+pub const MODEL_FORMAT_VERSION: u32 = 1;
+
+/// The `format` tag written into every model file.
+const MODEL_FORMAT_NAME: &str = "lorem-ipsumlike/markov-model";
+
+/// An error raised while saving or loading a [`MarkovModel`].
+#[derive(Debug)]
+pub enum ModelIoError {
+    /// The file could not be read or written.
+    Io(io::Error),
+    /// The JSON payload could not be encoded or decoded.
+    Json(serde_json::Error),
+    /// The binary payload could not be encoded or decoded.
+    Binary(bincode::Error),
+    /// The file was written by an incompatible version of the format.
+    UnsupportedVersion(u32),
+    /// The file decoded, but does not describe a consistent model.
+    Invalid(String),
+}
+
+impl fmt::Display for ModelIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelIoError::Io(_) => write!(f, "model file I/O failed"),
+            ModelIoError::Json(_) => write!(f, "invalid JSON model"),
+            ModelIoError::Binary(_) => write!(f, "invalid binary model"),
+            ModelIoError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported model format version {} (expected {})",
+                version, MODEL_FORMAT_VERSION
+            ),
+            ModelIoError::Invalid(reason) => write!(f, "inconsistent model: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ModelIoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelIoError::Io(err) => Some(err),
+            ModelIoError::Json(err) => Some(err),
+            ModelIoError::Binary(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ModelIoError {
+    fn from(err: io::Error) -> Self {
+        ModelIoError::Io(err)
+    }
+}
+
+/// The fields that identify a model file. Binary files carry the version in a fixed header
+/// after [`MODEL_MAGIC`]; JSON files start with these fields.
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelHeader {
+    format: String,
+    version: u32,
+}
+
+impl ModelHeader {
+    fn current() -> Self {
+        ModelHeader { format: MODEL_FORMAT_NAME.to_string(), version: MODEL_FORMAT_VERSION }
+    }
+
+    fn check(&self) -> Result<(), ModelIoError> {
+        if self.format != MODEL_FORMAT_NAME {
+            return Err(ModelIoError::Invalid(format!("unknown format tag {:?}", self.format)));
+        }
+        if self.version != MODEL_FORMAT_VERSION {
+            return Err(ModelIoError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+}
+
+/// A JSON model file: the header fields followed by the payload.
+#[derive(Debug, Serialize)]
+struct JsonModelFile<'a> {
+    #[serde(flatten)]
+    header: ModelHeader,
+    #[serde(flatten)]
+    model: &'a ModelFile,
+}
+
+/// The serialized form of a [`MarkovModel`], shared by the binary and JSON encodings.
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelFile {
+    order: usize,
+    vocab: Vec<String>,
+    lower: Vec<TokenId>,
+    /// One list of contexts per order, sorted so the output is stable.
+    tables: Vec<Vec<ContextEntry>>,
+    starts: Vec<Vec<TokenId>>,
+}
+
+/// One context and its successors with their (non-cumulative) counts.
+#[derive(Debug, Serialize, Deserialize)]
+struct ContextEntry {
+    context: Vec<TokenId>,
+    successors: Vec<TokenId>,
+    counts: Vec<u32>,
+}
+
+impl ModelFile {
+    fn from_model(model: &MarkovModel) -> Self {
+        let tables = model
+            .tables
+            .iter()
+            .map(|table| {
+                let mut entries: Vec<ContextEntry> = table
+                    .iter()
+                    .map(|(context, transitions)| {
+                        let (successors, counts) = transitions.weights().unzip();
+                        ContextEntry { context: context.clone(), successors, counts }
+                    })
+                    .collect();
+                entries.sort_by(|a, b| a.context.cmp(&b.context));
+                entries
+            })
+            .collect();
+        ModelFile {
+            order: model.order,
+            vocab: model.vocab.words.clone(),
+            lower: model.lower.clone(),
+            tables,
+            starts: model.starts.clone(),
+        }
+    }
+
+    /// Rebuilds the model, checking every id so a corrupt file cannot cause a panic later.
+    fn into_model(self) -> Result<MarkovModel, ModelIoError> {
+        let invalid = |reason: &str| Err(ModelIoError::Invalid(reason.to_string()));
+        if self.order == 0 || self.tables.len() != self.order {
+            return invalid("order does not match the number of tables");
+        }
+        if self.lower.len() != self.vocab.len() {
+            return invalid("lowercase map does not match the vocabulary");
+        }
+        let in_vocab = |ids: &[TokenId]| ids.iter().all(|&id| (id as usize) < self.vocab.len());
+        if !in_vocab(&self.lower) {
+            return invalid("token id out of range");
+        }
+
+        let mut model = MarkovModel::empty(self.order);
+        for (word, &lower) in self.vocab.iter().zip(&self.lower) {
+            if model.vocab.get(word).is_some() {
+                return invalid("duplicate vocabulary entry");
+            }
+            if model.vocab.intern(word).is_none() {
+                return invalid("vocabulary too large");
+            }
+            model.lower.push(lower);
+        }
+        for (k, entries) in self.tables.into_iter().enumerate() {
+            for entry in entries {
+                if entry.context.len() != k + 1
+                    || entry.successors.is_empty()
+                    || entry.successors.len() != entry.counts.len()
+                    || entry.counts.contains(&0)
+                    || !in_vocab(&entry.context)
+                    || !in_vocab(&entry.successors)
+                {
+                    return invalid("malformed transition table entry");
+                }
+                let transitions = Transitions::from_counts(entry.successors.into_iter().zip(entry.counts))
+                    .ok_or_else(|| ModelIoError::Invalid("transition counts overflow".to_string()))?;
+                model.tables[k].insert(entry.context, transitions);
+            }
+        }
+        if self.starts.iter().any(|start| start.is_empty() || start.len() > self.order || !in_vocab(start)) {
+            return invalid("malformed start run");
+        }
+        model.starts = self.starts;
+        model.index_sentence_starts();
+        Ok(model)
+    }
+}
+
+impl MarkovModel {
+    /// Writes the model to `writer` in the given format.
+    pub fn write_to<W: Write>(&self, mut writer: W, format: ModelFormat) -> Result<(), ModelIoError> {
+        let file = ModelFile::from_model(self);
+        match format {
+            ModelFormat::Binary => {
+                writer.write_all(MODEL_MAGIC)?;
+                writer.write_all(&MODEL_FORMAT_VERSION.to_le_bytes())?;
+                bincode::serialize_into(&mut writer, &file).map_err(ModelIoError::Binary)?;
+            }
+            ModelFormat::Json => {
+                let json = JsonModelFile { header: ModelHeader::current(), model: &file };
+                serde_json::to_writer_pretty(&mut writer, &json).map_err(ModelIoError::Json)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a model written by [`MarkovModel::write_to`], detecting the format from its first bytes.
+    ///
+    /// The format version is checked before the payload is decoded.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, ModelIoError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let file: ModelFile = match bytes.strip_prefix(MODEL_MAGIC.as_slice()) {
+            Some(rest) => {
+                let (version, payload) = rest
+                    .split_first_chunk()
+                    .ok_or_else(|| ModelIoError::Invalid("missing format version".to_string()))?;
+                ModelHeader { format: MODEL_FORMAT_NAME.to_string(), version: u32::from_le_bytes(*version) }.check()?;
+                bincode::deserialize(payload).map_err(ModelIoError::Binary)?
+            }
+            None => {
+                let header: ModelHeader = serde_json::from_slice(&bytes).map_err(ModelIoError::Json)?;
+                header.check()?;
+                serde_json::from_slice(&bytes).map_err(ModelIoError::Json)?
+            }
+        };
+        file.into_model()
+    }
+
+    /// Saves the model to a file in the given format.
+    pub fn save<P: AsRef<Path>>(&self, path: P, format: ModelFormat) -> Result<(), ModelIoError> {
+        let file = File::create(path)?;
+        self.write_to(BufWriter::new(file), format)
+    }
+
+    /// Loads a model saved with [`MarkovModel::save`] in either format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ModelIoError> {
+        let file = File::open(path)?;
+        Self::read_from(BufReader::new(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lorem::seeded_rng;
+
+    const PASSAGE: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_backoff_to_lower_order() {
+        let model = MarkovModel::train("the cat sat on the mat", 2);
+        // "the cat" was seen as a bigram context.
+        assert_eq!(model.successors(&["the", "cat"]), Some(vec![("sat", 1)]));
+        // "a cat" was never seen, so the model backs off to the unigram "cat".
+        assert_eq!(model.successors(&["a", "cat"]), Some(vec![("sat", 1)]));
+        // "the" on its own was followed by both "cat" and "mat".
+        assert_eq!(model.successors(&["The"]), Some(vec![("cat", 1), ("mat", 1)]));
+        assert_eq!(model.successors(&["dog"]), None);
+    }
+
+    #[test]
+    fn test_transitions_are_counted() {
+        let model = MarkovModel::train("lorem ipsum lorem ipsum lorem Dolor lorem ipsum", 1);
+        assert_eq!(model.successors(&["lorem"]), Some(vec![("ipsum", 3), ("Dolor", 1)]));
+        // "lorem", "ipsum", "Dolor" and the lowercased "dolor" are each interned once.
+        assert_eq!(model.vocab_len(), 4);
+    }
+
+    #[test]
+    fn test_transition_counts_saturate() {
+        let mut counts = TransitionCounts::default();
+        counts.add(0);
+        counts.counts[0].1 = u32::MAX;
+        counts.add(0);
+        assert_eq!(counts.counts, [(0, u32::MAX)]);
+
+        let counts = TransitionCounts { counts: vec![(0, u32::MAX - 2), (1, 5), (2, 3)], index: HashMap::new() };
+        let transitions = counts.into_transitions();
+        assert_eq!(transitions.total(), u32::MAX);
+        assert_eq!(transitions.weights().collect::<Vec<_>>(), [(0, u32::MAX - 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_weighted_sampling_follows_counts() {
+        let model = MarkovModel::train("a b a b a b a c", 1);
+        let mut rng = seeded_rng(11);
+        let mut b = 0;
+        for _ in 0..1000 {
+            let sample = model.generate_with_rng(2, &mut rng).unwrap();
+            if sample == "a b" {
+                b += 1;
+            }
+        }
+        // "a" is followed by "b" three times out of four; starts are "a" or "b" with equal odds.
+        assert!((300..450).contains(&b), "unexpected count {}", b);
+    }
+
+    #[test]
+    fn test_chain_round_trip() {
+        let chain = crate::lorem::build_markov_chain(PASSAGE);
+        let model = MarkovModel::from_chain(&chain);
+        assert_eq!(model.to_chain(), chain);
+        assert_eq!(chain["in"].len(), 3);
+    }
+
+    #[test]
+    fn test_higher_order_follows_the_seed() {
+        let model = MarkovModel::train(PASSAGE, 2);
+        assert_eq!(model.order(), 2);
+        let seed_trigrams: HashSet<Vec<String>> = words(PASSAGE)
+            .windows(3)
+            .map(|w| w.iter().map(|s| s.to_lowercase()).collect())
+            .collect();
+        for rng_seed in 0..20 {
+            let sample = model.generate_with_rng(40, &mut seeded_rng(rng_seed)).unwrap();
+            for trigram in words(&sample).windows(3) {
+                let trigram: Vec<String> = trigram.iter().map(|s| s.to_lowercase()).collect();
+                if trigram[..2].iter().any(|w| w == "laborum.") {
+                    // The final word has no successor, so generation restarted after it.
+                    continue;
+                }
+                assert!(seed_trigrams.contains(&trigram), "{:?} is not in the seed", trigram);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        let model = MarkovModel::train(PASSAGE, 3);
+        let first = model.generate_with_rng(30, &mut seeded_rng(9)).unwrap();
+        let second = model.generate_with_rng(30, &mut seeded_rng(9)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_short_seed() {
+        assert!(MarkovModel::train("lorem", 2).is_empty());
+        assert_eq!(MarkovModel::train("lorem", 2).generate(10), Err(GenerateError::EmptyModel));
+        assert_eq!(MarkovModel::train("", 1).generate(0), Ok(String::new()));
+        // A seed shorter than the order still generates from its lower-order tables,
+        // restarting each time it runs off the end of the seed.
+        let model = MarkovModel::train("lorem ipsum", 3);
+        assert_eq!(model.generate_with_rng(5, &mut seeded_rng(0)).unwrap(), "lorem ipsum lorem ipsum lorem");
+        assert_eq!(model.generate_with_rng(1, &mut seeded_rng(0)).unwrap(), "lorem");
+    }
+
+    #[test]
+    fn test_generates_exact_length() {
+        let seed = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+        for order in 1..=3 {
+            let model = MarkovModel::train(seed, order);
+            for rng_seed in 0..20 {
+                let sample = model.generate_with_rng(50, &mut seeded_rng(rng_seed)).unwrap();
+                assert_eq!(sample.split_whitespace().count(), 50, "{}", sample);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dead_end_restarts_at_sentence_start() {
+        let model = MarkovModel::train("the end. Lorem ipsum dolor", 1);
+        // "dolor" has no successor; the only capitalized start is "Lorem".
+        for rng_seed in 0..10 {
+            let sample = model.generate_with_rng(20, &mut seeded_rng(rng_seed)).unwrap();
+            assert!(!sample.contains("dolor the") && !sample.contains("dolor end."), "{}", sample);
+        }
+    }
+
+    #[test]
+    fn test_binary_and_json_round_trip() {
+        let model = MarkovModel::train(PASSAGE, 2);
+        for format in [ModelFormat::Binary, ModelFormat::Json] {
+            let mut bytes = Vec::new();
+            model.write_to(&mut bytes, format).unwrap();
+            let loaded = MarkovModel::read_from(bytes.as_slice()).unwrap();
+            assert_eq!(loaded, model);
+            assert_eq!(
+                loaded.generate_with_rng(30, &mut seeded_rng(4)).unwrap(),
+                model.generate_with_rng(30, &mut seeded_rng(4)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_is_versioned_and_readable() {
+        let mut bytes = Vec::new();
+        MarkovModel::train("lorem ipsum dolor", 1).write_to(&mut bytes, ModelFormat::Json).unwrap();
+        let json = String::from_utf8(bytes).unwrap();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"ipsum\""));
+
+        assert!(json.trim_start_matches(['{', ' ', '\n']).starts_with("\"format\""));
+
+        let future = json.replace("\"version\": 1", "\"version\": 99");
+        assert!(matches!(
+            MarkovModel::read_from(future.as_bytes()),
+            Err(ModelIoError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn test_version_is_checked_before_the_payload() {
+        // A future layout whose payload this version cannot decode.
+        let json = format!("{{\"format\": \"{}\", \"version\": 2, \"layers\": [[1, 2]]}}", MODEL_FORMAT_NAME);
+        assert!(matches!(MarkovModel::read_from(json.as_bytes()), Err(ModelIoError::UnsupportedVersion(2))));
+        let mut binary = MODEL_MAGIC.to_vec();
+        binary.extend_from_slice(&2u32.to_le_bytes());
+        binary.extend_from_slice(b"\xff\xff");
+        assert!(matches!(MarkovModel::read_from(binary.as_slice()), Err(ModelIoError::UnsupportedVersion(2))));
+
+        let mut bytes = Vec::new();
+        MarkovModel::train("lorem ipsum dolor", 1).write_to(&mut bytes, ModelFormat::Binary).unwrap();
+        assert_eq!(bytes[MODEL_MAGIC.len()..MODEL_MAGIC.len() + 4], MODEL_FORMAT_VERSION.to_le_bytes());
+    }
+
+    #[test]
+    fn test_corrupt_models_are_rejected() {
+        let mut bytes = Vec::new();
+        MarkovModel::train("lorem ipsum dolor", 1).write_to(&mut bytes, ModelFormat::Json).unwrap();
+        let json = String::from_utf8(bytes).unwrap();
+        let corrupt = json.replacen("\"starts\": [\n    [\n      0", "\"starts\": [\n    [\n      42", 1);
+        assert_ne!(corrupt, json);
+        assert!(matches!(MarkovModel::read_from(corrupt.as_bytes()), Err(ModelIoError::Invalid(_))));
+        assert!(matches!(MarkovModel::read_from(&b"LIPSUMMK\x01"[..]), Err(ModelIoError::Invalid(_))));
+        assert!(matches!(MarkovModel::read_from(&b"LIPSUMMK\x01\0\0\0\x01"[..]), Err(ModelIoError::Binary(_))));
+        assert!(matches!(MarkovModel::read_from(&b"not a model"[..]), Err(ModelIoError::Json(_))));
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let model = MarkovModel::train(PASSAGE, 1);
+        let path = std::env::temp_dir().join(format!("lorem-ipsumlike-model-{}.bin", std::process::id()));
+        model.save(&path, ModelFormat::from_path(&path)).unwrap();
+        let loaded = MarkovModel::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), model);
+        assert_eq!(ModelFormat::from_path(Path::new("model.JSON")), ModelFormat::Json);
+    }
+}
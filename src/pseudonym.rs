@@ -0,0 +1,265 @@
+//! Keyed, consistent pseudonymization of real words as lorem words.
+//!
+//! A [`Pseudonymizer`] replaces every distinct word of a text with a lorem-like word of the same
+//! [`LengthClass`], in the same case, and always the same one: "Alice" and "alice" both become
+//! "Dolor" and "dolor" for as long as the pseudonymizer is used. Pseudonyms are built from Latin
+//! syllables picked by a keyed hash of the word. Numbers, punctuation and whitespace are kept as
+//! they are.
+//!
+//! A word's pseudonym is a function of the key and the word alone, so the same key maps the same
+//! word the same way in every document, whatever words come before it. Only when two words of
+//! one document hash to the same syllables does a keyed counter move the later one on to its
+//! next candidate. The [`table`](Pseudonymizer::table) maps lowercased real words to pseudonyms
+//! and can be serialized for audit; [`Pseudonymizer::with_table`] continues one.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::greeking::{letter_runs, match_case};
+use crate::watermark::keyed_hash;
+
+const SYLLABLE_LABEL: &[u8] = b"lorem-ipsumlike/pseudonym/syllables";
+
+/// How many syllable words of one length are tried before trying longer ones.
+const ATTEMPTS_PER_LENGTH: u64 = 64;
+/// How many syllable words are tried before a pseudonym may leave its length class.
+const ATTEMPTS_IN_CLASS: u64 = 4096;
+
+/// Syllables the generator builds pseudonyms from.
+const SYLLABLES: &[&str] = &[
+    "a", "ae", "am", "an", "bi", "bo", "ca", "ci", "con", "cu", "da", "de", "di", "do", "du", "e", "el", "em",
+    "en", "er", "es", "et", "fa", "fe", "fi", "ga", "gi", "i", "im", "in", "ip", "is", "it", "la", "le", "li",
+    "lo", "lor", "lu", "ma", "me", "mi", "mo", "mu", "na", "ne", "ni", "no", "nu", "o", "or", "pa", "pe", "pi",
+    "por", "pu", "qua", "que", "qui", "ra", "re", "ri", "ro", "ru", "sa", "se", "si", "so", "sum", "ta", "te",
+    "ti", "to", "tu", "tur", "u", "ul", "um", "unt", "us", "ut", "va", "ve", "vi", "vo",
+];
+
+/// A band of word lengths that a pseudonym stays within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LengthClass {
+    /// One to three letters.
+    Short,
+    /// Four to six letters.
+    Medium,
+    /// Seven to nine letters.
+    Long,
+    /// Ten letters or more.
+    VeryLong,
+}
+
+impl LengthClass {
+    /// The class of a word of `len` letters.
+    pub fn of(len: usize) -> Self {
+        match len {
+            0..=3 => LengthClass::Short,
+            4..=6 => LengthClass::Medium,
+            7..=9 => LengthClass::Long,
+            _ => LengthClass::VeryLong,
+        }
+    }
+
+    /// The longest length in the class, if it has one.
+    pub fn max_len(self) -> Option<usize> {
+        match self {
+            LengthClass::Short => Some(3),
+            LengthClass::Medium => Some(6),
+            LengthClass::Long => Some(9),
+            LengthClass::VeryLong => None,
+        }
+    }
+}
+
+/// Maps the words of texts to lorem pseudonyms under a secret key.
+#[derive(Clone)]
+pub struct Pseudonymizer {
+    key: Vec<u8>,
+    /// Lowercased real word to lowercase pseudonym.
+    table: BTreeMap<String, String>,
+    /// Pseudonyms already handed out, so no two words share one.
+    used: HashSet<String>,
+}
+
+impl std::fmt::Debug for Pseudonymizer {
+    /// Leaves the key out, so it does not end up in logs.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pseudonymizer").field("table", &self.table).finish_non_exhaustive()
+    }
+}
+
+impl Pseudonymizer {
+    /// Creates a pseudonymizer with an empty table.
+    pub fn new(key: &[u8]) -> Self {
+        Pseudonymizer::with_table(key, BTreeMap::new())
+    }
+
+    /// Creates a pseudonymizer that continues a table saved from an earlier one, so words it
+    /// has seen keep their pseudonyms even where they were moved on by a collision.
+    pub fn with_table(key: &[u8], table: BTreeMap<String, String>) -> Self {
+        let used = table.values().cloned().collect();
+        Pseudonymizer { key: key.to_vec(), table, used }
+    }
+
+    /// The mapping so far, from lowercased real words to lowercase pseudonyms.
+    pub fn table(&self) -> &BTreeMap<String, String> {
+        &self.table
+    }
+
+    /// Consumes the pseudonymizer, returning its table.
+    pub fn into_table(self) -> BTreeMap<String, String> {
+        self.table
+    }
+
+    /// The real word a pseudonym stands for, if it was handed out.
+    pub fn original_of(&self, pseudonym: &str) -> Option<&str> {
+        let pseudonym = pseudonym.to_lowercase();
+        self.table.iter().find(|(_, p)| **p == pseudonym).map(|(word, _)| word.as_str())
+    }
+
+    /// The lowercase pseudonym of `word`, assigning one if the word is new.
+    pub fn pseudonym(&mut self, word: &str) -> String {
+        let word = word.to_lowercase();
+        if let Some(pseudonym) = self.table.get(&word) {
+            return pseudonym.clone();
+        }
+        let len = word.chars().count();
+        let pseudonym = self.syllable_word(&word, len);
+        self.used.insert(pseudonym.clone());
+        self.table.insert(word, pseudonym.clone());
+        pseudonym
+    }
+
+    /// Replaces every word of `text` with its pseudonym in the word's case, keeping everything
+    /// else as it is. Apostrophes, hyphens and digits split words, as in greeking.
+    pub fn pseudonymize(&mut self, text: &str) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut position = 0;
+        for run in letter_runs(text) {
+            let original = &text[run.clone()];
+            output.push_str(&text[position..run.start]);
+            output.push_str(&match_case(original, &self.pseudonym(original)));
+            position = run.end;
+        }
+        output.push_str(&text[position..]);
+        output
+    }
+
+    /// The first unused word of `len` letters made of syllables picked by the key, the word and
+    /// an attempt counter. Each attempt depends on nothing else, so a word that collides with
+    /// no other keeps its first candidate. When every attempt at one length is taken, the word
+    /// grows, within its length class for as long as the class has room.
+    fn syllable_word(&self, word: &str, len: usize) -> String {
+        let len = len.max(1);
+        let max_len = LengthClass::of(len).max_len();
+        (0u64..)
+            .map(|attempt| {
+                let grown = len + (attempt / ATTEMPTS_PER_LENGTH) as usize;
+                let len = match max_len {
+                    Some(max) if attempt < ATTEMPTS_IN_CLASS => grown.min(max),
+                    _ => grown,
+                };
+                let mut built = String::new();
+                let mut block = 0u64;
+                while built.len() < len {
+                    let bytes =
+                        keyed_hash(&self.key, &[SYLLABLE_LABEL, word.as_bytes(), &attempt.to_le_bytes(), &block.to_le_bytes()]);
+                    for byte in bytes {
+                        if built.len() >= len {
+                            break;
+                        }
+                        built.push_str(SYLLABLES[byte as usize % SYLLABLES.len()]);
+                    }
+                    block += 1;
+                }
+                built.truncate(len);
+                built
+            })
+            .find(|candidate| !self.used.contains(candidate))
+            .expect("words grow without bound, so one is eventually unused")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "Alice Smith called about order 4411. ALICE says the invoice for Smith & Sons is wrong;\nalice wants a refund.";
+
+    #[test]
+    fn test_mapping_is_consistent_and_keeps_case() {
+        let mut pseudonymizer = Pseudonymizer::new(b"key");
+        let output = pseudonymizer.pseudonymize(REPORT);
+        for name in ["Alice", "Smith", "invoice", "refund"] {
+            assert!(!output.to_lowercase().contains(&name.to_lowercase()), "{}", output);
+        }
+        assert!(output.contains(" 4411. ") && output.contains(" & ") && output.contains(";\n"));
+
+        let alice = pseudonymizer.table()["alice"].clone();
+        let capitalized = match_case("Alice", &alice);
+        assert!(output.starts_with(&capitalized), "{}", output);
+        assert!(output.contains(&alice.to_uppercase()) && output.contains(&format!("\n{} ", alice)));
+        assert_eq!(pseudonymizer.original_of(&capitalized), Some("alice"));
+
+        // Every distinct word has its own pseudonym in its own length class.
+        let pseudonyms: HashSet<&String> = pseudonymizer.table().values().collect();
+        assert_eq!(pseudonyms.len(), pseudonymizer.table().len());
+        for (word, pseudonym) in pseudonymizer.table() {
+            assert_eq!(LengthClass::of(word.chars().count()), LengthClass::of(pseudonym.chars().count()), "{} -> {}", word, pseudonym);
+        }
+    }
+
+    #[test]
+    fn test_key_decides_the_mapping() {
+        let first = Pseudonymizer::new(b"key").pseudonymize(REPORT);
+        assert_eq!(Pseudonymizer::new(b"key").pseudonymize(REPORT), first);
+        assert_ne!(Pseudonymizer::new(b"other key").pseudonymize(REPORT), first);
+
+        // A saved table carries the mapping over to the next document.
+        let mut pseudonymizer = Pseudonymizer::new(b"key");
+        pseudonymizer.pseudonymize(REPORT);
+        let saved = serde_json::to_string(pseudonymizer.table()).unwrap();
+        let mut next = Pseudonymizer::with_table(b"key", serde_json::from_str(&saved).unwrap());
+        let alice = pseudonymizer.pseudonym("alice");
+        assert_eq!(next.pseudonymize("Hello Alice"), format!("{} {}", match_case("Hello", &next.pseudonym("hello")), match_case("Alice", &alice)));
+        // The saved words keep their pseudonyms whatever order the next document uses.
+        let mut reordered = Pseudonymizer::with_table(b"key", serde_json::from_str(&saved).unwrap());
+        reordered.pseudonymize("Refund the wrong invoice, Smith. Hello Alice.");
+        for (word, pseudonym) in pseudonymizer.table() {
+            assert_eq!(&reordered.table()[word], pseudonym, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_documents_share_the_mapping() {
+        let mut first = Pseudonymizer::new(b"key");
+        first.pseudonymize(REPORT);
+        let mut second = Pseudonymizer::new(b"key");
+        second.pseudonymize("Bob forwarded the refund request to Smith; Alice had filed the invoice twice.");
+        let shared: Vec<&String> = second.table().keys().filter(|word| first.table().contains_key(*word)).collect();
+        assert!(shared.len() >= 5, "{:?}", shared);
+        for word in shared {
+            assert_eq!(first.table()[word], second.table()[word], "{}", word);
+        }
+        // Words seen before do not move a later word.
+        let alone = Pseudonymizer::new(b"key").pseudonym("alice");
+        let mut after = Pseudonymizer::new(b"key");
+        after.pseudonymize("bob carol dave");
+        assert_eq!(after.pseudonym("alice"), alone);
+    }
+
+    #[test]
+    fn test_collisions_stay_in_the_length_class() {
+        let mut pseudonymizer = Pseudonymizer::new(b"key");
+        let many: Vec<String> = (0..120).map(|i| format!("{}{}", ["alpha", "bravo", "delta", "gamma"][i % 4], "abcdefghijklmnopqrstuvwxyz".chars().nth(i / 4 % 26).unwrap())).collect();
+        for word in &many {
+            pseudonymizer.pseudonym(word);
+        }
+        let pseudonyms: Vec<&String> = pseudonymizer.table().values().collect();
+        assert_eq!(pseudonyms.iter().collect::<HashSet<_>>().len(), pseudonyms.len());
+        for pseudonym in pseudonyms {
+            assert_eq!(pseudonym.len(), 6, "{}", pseudonym);
+        }
+        // There are only a few one-letter pseudonyms, so short words soon need the counter.
+        let letters: Vec<String> = ('a'..='z').map(|c| pseudonymizer.pseudonym(&c.to_string())).collect();
+        assert_eq!(letters.iter().collect::<HashSet<_>>().len(), 26);
+        assert!(letters.iter().all(|p| LengthClass::of(p.chars().count()) == LengthClass::Short), "{:?}", letters);
+    }
+}
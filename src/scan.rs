@@ -0,0 +1,257 @@
+//! Scanning a directory tree for placeholder text that was left behind.
+//!
+//! [`scan_dir`] walks a tree with the same rules as `git` (`.gitignore`, `.ignore` and hidden
+//! files are skipped), runs [`LoremDetector::detect_spans`] over every UTF-8 text file and
+//! reports each filler span as a [`Finding`] with a 1-based line and column. Files that cannot
+//! be read are reported in the [`ScanReport`] too, without stopping the walk.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::detector::{LoremDetector, SpanGranularity};
+use crate::matcher::WordMatcher;
+
+/// The longest excerpt, in characters, included in a [`Finding`].
+const EXCERPT_CHARS: usize = 60;
+
+/// Controls how files are scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// How words are grouped before scoring.
+    pub granularity: SpanGranularity,
+    /// Spans with fewer words than this are not reported; isolated identifiers in code
+    /// often look Latin on their own.
+    pub min_words: usize,
+}
+
+impl Default for ScanOptions {
+    /// Sentence granularity, reporting spans of at least three words.
+    fn default() -> Self {
+        ScanOptions { granularity: SpanGranularity::Sentences, min_words: 3 }
+    }
+}
+
+/// A filler span found in a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// The file the span was found in.
+    pub path: PathBuf,
+    /// The 1-based line the span starts on.
+    pub line: usize,
+    /// The 1-based column, in characters, the span starts at.
+    pub column: usize,
+    /// Byte range of the span in the file.
+    pub range: Range<usize>,
+    /// The detector's score for the span.
+    pub score: f64,
+    /// The start of the span, cut at the end of its first line or after 60 characters.
+    pub excerpt: String,
+}
+
+impl fmt::Display for Finding {
+    /// Formats the finding as `path:line:column: filler (score S): excerpt`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: filler (score {:.2}): {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.score,
+            self.excerpt
+        )
+    }
+}
+
+/// An error raised while walking a directory tree.
+#[derive(Debug)]
+pub enum ScanError {
+    /// The tree could not be walked, e.g. a directory could not be listed.
+    Walk(ignore::Error),
+    /// A file could not be read.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Walk(_) => write!(f, "failed to walk the directory tree"),
+            ScanError::Io(path, _) => write!(f, "failed to read {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::Walk(err) => Some(err),
+            ScanError::Io(_, err) => Some(err),
+        }
+    }
+}
+
+impl From<ignore::Error> for ScanError {
+    fn from(err: ignore::Error) -> Self {
+        ScanError::Walk(err)
+    }
+}
+
+/// Everything [`scan_dir`] found in a tree.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// The filler spans, ordered by path.
+    pub findings: Vec<Finding>,
+    /// The parts of the tree that could not be scanned, in the order they were reached.
+    pub errors: Vec<ScanError>,
+}
+
+/// Finds the filler spans in one file's contents, reporting them against `path`.
+pub fn scan_file_text(
+    detector: &LoremDetector,
+    dictionary: &WordMatcher,
+    path: &Path,
+    text: &str,
+    options: &ScanOptions,
+) -> Vec<Finding> {
+    detector
+        .detect_spans(dictionary, text, options.granularity)
+        .into_iter()
+        .filter(|span| span.words >= options.min_words)
+        .map(|span| {
+            let before = &text[..span.range.start];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            let excerpt = text[span.range.clone()].lines().next().unwrap_or_default();
+            Finding {
+                path: path.to_path_buf(),
+                line: before.matches('\n').count() + 1,
+                column: before[line_start..].chars().count() + 1,
+                score: span.score,
+                excerpt: excerpt.chars().take(EXCERPT_CHARS).collect(),
+                range: span.range,
+            }
+        })
+        .collect()
+}
+
+/// Walks `root`, honouring ignore files, and returns the filler found in every text file,
+/// ordered by path. Files that are not valid UTF-8 or contain NUL bytes are skipped as binary.
+/// Files and directories that cannot be read are recorded in [`ScanReport::errors`] and the
+/// walk carries on with the rest of the tree. `dictionary` is compiled once by the caller
+/// and shared by every file.
+pub fn scan_dir(detector: &LoremDetector, dictionary: &WordMatcher, root: &Path, options: &ScanOptions) -> ScanReport {
+    let walker = ignore::WalkBuilder::new(root)
+        // Apply .gitignore files even when the tree is not (yet) a git checkout.
+        .require_git(false)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+    let mut report = ScanReport::default();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                report.errors.push(err.into());
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let bytes = match fs::read(entry.path()) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                report.errors.push(ScanError::Io(entry.path().to_path_buf(), err));
+                continue;
+            }
+        };
+        if bytes.contains(&0) {
+            continue;
+        }
+        if let Ok(text) = String::from_utf8(bytes) {
+            report.findings.extend(scan_file_text(detector, dictionary, entry.path(), &text, options));
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::english;
+
+    const LOREM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+
+    fn scan(root: &Path) -> ScanReport {
+        let detector = LoremDetector::from_seed(LOREM);
+        scan_dir(&detector, &WordMatcher::from_speller(&english::speller()), root, &ScanOptions::default())
+    }
+
+    #[test]
+    fn test_reports_line_and_column() {
+        let detector = LoremDetector::from_seed(LOREM);
+        let dictionary = WordMatcher::from_speller(&english::speller());
+        let text = format!("# Title\n\nPlease read the guide first.\n  {}\n", LOREM);
+        let findings = scan_file_text(&detector, &dictionary, Path::new("README.md"), &text, &ScanOptions::default());
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        assert_eq!((findings[0].line, findings[0].column), (4, 3));
+        assert!(findings[0].to_string().starts_with("README.md:4:3: filler (score "));
+        assert!(findings[0].excerpt.starts_with("Lorem ipsum dolor sit amet"));
+        assert_eq!(findings[0].excerpt.chars().count(), EXCERPT_CHARS);
+
+        // A two-word label is below the default minimum span length.
+        let short = "let label = \"Lorem ipsum\";\n";
+        let path = Path::new("ui.rs");
+        assert!(scan_file_text(&detector, &dictionary, path, short, &ScanOptions::default()).is_empty());
+        let options = ScanOptions { min_words: 1, ..ScanOptions::default() };
+        let findings = scan_file_text(&detector, &dictionary, path, short, &options);
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        assert_eq!(&short[findings[0].range.clone()], "Lorem ipsum");
+    }
+
+    #[test]
+    fn test_scan_dir_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "ignored/\n").unwrap();
+        fs::create_dir_all(dir.path().join("ignored")).unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("ignored/fixture.txt"), LOREM).unwrap();
+        fs::write(dir.path().join("docs/guide.md"), format!("Welcome to the guide.\n\n{}\n", LOREM)).unwrap();
+        fs::write(dir.path().join("docs/clean.md"), "Click the button to download the installer.\n").unwrap();
+        fs::write(dir.path().join("logo.bin"), b"Lorem\0ipsum").unwrap();
+
+        let report = scan(dir.path());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.findings.len(), 1, "{:?}", report.findings);
+        assert_eq!(report.findings[0].path, dir.path().join("docs/guide.md"));
+        assert_eq!((report.findings[0].line, report.findings[0].column), (3, 1));
+    }
+
+    #[test]
+    fn test_errors_do_not_stop_the_scan() {
+        let report = scan(&std::env::temp_dir().join(format!("lorem-ipsumlike-missing-{}", std::process::id())));
+        assert!(report.findings.is_empty());
+        assert!(matches!(report.errors[..], [ScanError::Walk(_)]), "{:?}", report.errors);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), LOREM).unwrap();
+        let locked = dir.path().join("b.md");
+        fs::write(&locked, LOREM).unwrap();
+        fs::write(dir.path().join("c.md"), LOREM).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        }
+        let report = scan(dir.path());
+        // Permissions do not stop root, so only expect the error where they apply.
+        if fs::read(&locked).is_err() {
+            assert!(matches!(&report.errors[..], [ScanError::Io(path, _)] if *path == locked), "{:?}", report.errors);
+            assert_eq!(report.findings.len(), 2, "{:?}", report.findings);
+        } else {
+            assert_eq!(report.findings.len(), 3, "{:?}", report.findings);
+        }
+        assert_eq!(report.findings.last().unwrap().path, dir.path().join("c.md"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Splitting run-together words into filler and real words.
+//!
+//! [`IpsumStatus::Partial`](crate::ipsum::IpsumStatus::Partial) only reports the longest
+//! dictionary word inside a token. [`segment_word`] instead finds the most probable way to
+//! read the whole token as a sequence of corpus and dictionary words, so "loremtomatoipsumpotato"
+//! comes apart as "lorem", "tomato", "ipsum", "potato". It is the classic unigram segmenter:
+//! dynamic programming over every split point, scoring each piece by its negative log
+//! probability from the `Speller.n_words` counts.
+
+use std::ops::Range;
+
+use spellcheck::Speller;
+
+use crate::dolor;
+use crate::ipsum::LoweredWord;
+
+/// What a [`Segment`] of a word was recognized as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// A word of the filler corpus.
+    Filler,
+    /// A word of the speller's dictionary.
+    Real,
+    /// Characters that are neither.
+    Unknown,
+}
+
+/// A piece of a segmented word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The piece, as it appears in the original word.
+    pub text: String,
+    /// Byte range of the piece in the original word.
+    pub range: Range<usize>,
+    /// What the piece was recognized as.
+    pub kind: SegmentKind,
+}
+
+/// Unigram probabilities for the segmenter, as negative natural logs.
+struct Costs<'a> {
+    speller: &'a Speller,
+    /// `ln` of the total count of the dictionary.
+    ln_total: f64,
+    /// The cost of a corpus word: that of a dictionary word of average frequency.
+    filler: f64,
+}
+
+impl<'a> Costs<'a> {
+    fn new(speller: &'a Speller) -> Self {
+        let total: f64 = speller.n_words.values().map(|&count| f64::from(count)).sum::<f64>().max(1.0);
+        let mean = total / speller.n_words.len().max(1) as f64;
+        Costs { speller, ln_total: total.ln(), filler: total.ln() - mean.ln() }
+    }
+
+    /// The cheapest reading of `piece` as a single word.
+    fn word(&self, piece: &str) -> Option<(f64, SegmentKind)> {
+        if dolor::corpus_matcher().contains(piece) {
+            return Some((self.filler, SegmentKind::Filler));
+        }
+        let count = *self.speller.n_words.get(piece)?;
+        Some((self.ln_total - f64::from(count.max(1)).ln(), SegmentKind::Real))
+    }
+
+    /// The cost of `chars` characters that are no word at all: the probability 10 / (N · 10^len)
+    /// used by Norvig's segmenter, which makes long unknown runs ever less likely.
+    fn unknown(&self, chars: usize) -> f64 {
+        self.ln_total + (chars as f64 - 1.0) * std::f64::consts::LN_10
+    }
+}
+
+/// Splits `word` into the most probable sequence of corpus words, dictionary words and
+/// unknown runs. Matching ignores case; the returned ranges and texts refer to the original.
+///
+/// Corpus words count as dictionary words of average frequency, so a real word that happens
+/// to be hidden in filler wins over splitting it into shorter words. Adjacent unknown
+/// characters are reported as one segment.
+pub fn segment_word(speller: &Speller, word: &str) -> Vec<Segment> {
+    let lower = LoweredWord::new(word);
+    let text = lower.as_str();
+    let bounds: Vec<usize> = text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+    let costs = Costs::new(speller);
+
+    // best[j] is the cheapest segmentation of the first j characters: its cost, where its
+    // last piece starts, and what that piece is.
+    let mut best: Vec<(f64, usize, SegmentKind)> = vec![(0.0, 0, SegmentKind::Unknown)];
+    for j in 1..bounds.len() {
+        let mut cheapest = (f64::INFINITY, 0, SegmentKind::Unknown);
+        for i in 0..j {
+            let piece = &text[bounds[i]..bounds[j]];
+            let (cost, kind) = costs.word(piece).unwrap_or((costs.unknown(j - i), SegmentKind::Unknown));
+            let total = best[i].0 + cost;
+            if total < cheapest.0 {
+                cheapest = (total, i, kind);
+            }
+        }
+        best.push(cheapest);
+    }
+
+    let mut pieces = Vec::new();
+    let mut j = bounds.len() - 1;
+    while j > 0 {
+        let (_, i, kind) = best[j];
+        pieces.push((bounds[i]..bounds[j], kind));
+        j = i;
+    }
+    pieces.reverse();
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for (range, kind) in pieces {
+        let range = lower.original_range(range);
+        match segments.last_mut() {
+            // Grapheme rounding can make neighbours overlap; unknown runs merge anyway.
+            Some(last) if last.range.end > range.start || (kind == SegmentKind::Unknown && last.kind == kind) => {
+                last.range.end = last.range.end.max(range.end);
+                last.text = word[last.range.clone()].to_string();
+                if last.kind != kind {
+                    last.kind = SegmentKind::Unknown;
+                }
+            }
+            _ => segments.push(Segment { text: word[range.clone()].to_string(), range, kind }),
+        }
+    }
+    segments
+}
+
+/// The real words hidden in `word`, with their ranges in it: the [`SegmentKind::Real`]
+/// segments of [`segment_word`].
+pub fn recover_real_words(speller: &Speller, word: &str) -> Vec<Segment> {
+    segment_word(speller, word)
+        .into_iter()
+        .filter(|segment| segment.kind == SegmentKind::Real)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::english;
+    use std::collections::HashMap;
+
+    fn trained(training: &str) -> Speller {
+        let mut speller = Speller { letters: "abcdefghijklmnopqrstuvwxyz".to_string(), n_words: HashMap::new() };
+        speller.train(training);
+        speller
+    }
+
+    fn pieces(segments: &[Segment]) -> Vec<(&str, SegmentKind)> {
+        segments.iter().map(|s| (s.text.as_str(), s.kind)).collect()
+    }
+
+    #[test]
+    fn test_segments_concatenated_filler() {
+        let speller = trained("tomato potato soup");
+        let segments = segment_word(&speller, "loremtomatoipsumpotato");
+        assert_eq!(
+            pieces(&segments),
+            [
+                ("lorem", SegmentKind::Filler),
+                ("tomato", SegmentKind::Real),
+                ("ipsum", SegmentKind::Filler),
+                ("potato", SegmentKind::Real)
+            ]
+        );
+        let real = recover_real_words(&speller, "loremtomatoipsumpotato");
+        assert_eq!(real.iter().map(|s| s.range.clone()).collect::<Vec<_>>(), [5..11, 16..22]);
+
+        // Unknown characters form one run, and ranges refer to the original case.
+        let segments = segment_word(&speller, "TomatoXQZDolor");
+        assert_eq!(
+            pieces(&segments),
+            [("Tomato", SegmentKind::Real), ("XQZ", SegmentKind::Unknown), ("Dolor", SegmentKind::Filler)]
+        );
+        assert!(segment_word(&speller, "").is_empty());
+    }
+
+    #[test]
+    fn test_prefers_probable_words() {
+        let speller = english::speller();
+        let words = |word| recover_real_words(&speller, word).into_iter().map(|s| s.text).collect::<Vec<_>>();
+        assert_eq!(words("loremthehouseipsum"), ["the", "house"]);
+        assert_eq!(words("consecturpeopleamet"), ["people"]);
+
+        // Ranges stay valid when lowercasing changes byte lengths.
+        let mut speller = trained("");
+        speller.n_words.insert("café".to_string(), 1);
+        let segments = segment_word(&speller, "LOREMCAFÉ");
+        assert_eq!(segments.last().map(|s| (s.text.as_str(), s.range.clone())), Some(("CAFÉ", 5..10)));
+    }
+}
@@ -0,0 +1,312 @@
+//! Hiding bytes in generated Lorem Ipsum.
+//!
+//! A [`StegoCodec`] turns a byte payload into text that a [`MarkovModel`] could have generated,
+//! and turns such text back into the payload. It works like an arithmetic decoder run in
+//! reverse: the payload is read as a binary fraction, and at every step the current interval
+//! is split among the model's successors of the last words in proportion to their counts.
+//! The word whose share contains the payload is emitted, so likely words take few bits and
+//! the text reads like ordinary output of the model. Decoding replays the same splits from
+//! the words and reads the payload back off the interval.
+//!
+//! The payload is prefixed with its length, so decoding needs nothing but the text and the
+//! same model. The text is not encrypted: anyone with the model can decode it.
+
+use std::fmt;
+
+use crate::model::MarkovModel;
+
+/// Bits of precision of the coding interval.
+const PRECISION: u32 = 32;
+/// The full coding interval, `[0, WHOLE)`.
+const WHOLE: u64 = 1 << PRECISION;
+/// How many words in a row may carry no information before encoding gives up.
+const MAX_IDLE_WORDS: usize = 256;
+
+/// An error raised while encoding or decoding a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StegoError {
+    /// The model has no words to choose from.
+    EmptyModel,
+    /// The model kept offering a single successor, so the text could not carry any more bits.
+    NoEntropy,
+    /// A word of the text is not one the model could have produced at that point, so the
+    /// text was not encoded with this model or was changed afterwards.
+    UnknownWord {
+        /// Index of the word among the whitespace-separated words of the text.
+        index: usize,
+        /// The word.
+        word: String,
+    },
+    /// The text ends before the whole payload has been read back.
+    Truncated,
+}
+
+impl fmt::Display for StegoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StegoError::EmptyModel => write!(f, "the model is empty; train it on at least two words"),
+            StegoError::NoEntropy => write!(f, "the model offers no choice of words to encode with"),
+            StegoError::UnknownWord { index, word } => {
+                write!(f, "word {} ({:?}) cannot have been produced by the model", index, word)
+            }
+            StegoError::Truncated => write!(f, "the text ends before the payload does"),
+        }
+    }
+}
+
+impl std::error::Error for StegoError {}
+
+/// The coding interval, `[low, high)` within `[0, WHOLE)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Interval {
+    low: u64,
+    high: u64,
+}
+
+impl Interval {
+    const FULL: Interval = Interval { low: 0, high: WHOLE };
+
+    /// Splits the interval among candidates in proportion to their counts. Shares that round
+    /// to nothing are empty; together the shares always cover the whole interval.
+    fn split<'w>(&self, candidates: &[(&'w str, u32)]) -> Vec<(&'w str, Interval)> {
+        let total: u128 = candidates.iter().map(|&(_, count)| u128::from(count)).sum();
+        let width = u128::from(self.high - self.low);
+        let mut cumulative = 0;
+        let mut bound = self.low;
+        candidates
+            .iter()
+            .map(|&(word, count)| {
+                cumulative += u128::from(count);
+                let next = self.low + (width * cumulative / total) as u64;
+                let share = Interval { low: bound, high: next };
+                bound = next;
+                (word, share)
+            })
+            .collect()
+    }
+
+    /// Shifts out the leading bits that `low` and `high - 1` share, which no later
+    /// narrowing can change, appending them to `bits`.
+    fn settle(&mut self, bits: &mut Vec<bool>) {
+        let last = self.high - 1;
+        let shared = ((self.low ^ last) << (64 - PRECISION)).leading_zeros().min(PRECISION);
+        for i in 0..shared {
+            bits.push(self.low >> (PRECISION - 1 - i) & 1 == 1);
+        }
+        if shared == PRECISION {
+            *self = Interval::FULL;
+        } else if shared > 0 {
+            let mask = WHOLE - 1;
+            self.low = (self.low << shared) & mask;
+            self.high = (((last << shared) | ((1 << shared) - 1)) & mask) + 1;
+        }
+    }
+}
+
+/// Encodes bytes as model-like text and decodes them back.
+#[derive(Debug, Clone)]
+pub struct StegoCodec<'m> {
+    model: &'m MarkovModel,
+    /// Word counts, sorted by word, for the first word and for dead ends.
+    unigrams: Vec<(String, u32)>,
+}
+
+impl<'m> StegoCodec<'m> {
+    /// Creates a codec over `model`. Returns [`StegoError::EmptyModel`] if the model has no words.
+    pub fn new(model: &'m MarkovModel) -> Result<Self, StegoError> {
+        if model.is_empty() {
+            return Err(StegoError::EmptyModel);
+        }
+        let mut unigrams: Vec<(String, u32)> = model.word_counts().into_iter().collect();
+        unigrams.sort_unstable();
+        Ok(StegoCodec { model, unigrams })
+    }
+
+    /// The words that may follow `previous`, in a fixed order, with their counts.
+    fn candidates(&self, previous: &[&str]) -> Vec<(&str, u32)> {
+        let context = &previous[previous.len().saturating_sub(self.model.order())..];
+        let mut candidates = match (context.is_empty(), self.model.successors(context)) {
+            (false, Some(successors)) => successors,
+            _ => self.unigrams.iter().map(|(word, count)| (word.as_str(), *count)).collect(),
+        };
+        candidates.sort_unstable();
+        candidates
+    }
+
+    /// Encodes `payload` as space-separated words. The first word is capitalized.
+    ///
+    /// Returns [`StegoError::NoEntropy`] if the model gets stuck on words with a single
+    /// successor, which cannot carry any bits.
+    pub fn encode(&self, payload: &[u8]) -> Result<String, StegoError> {
+        let mut message = Vec::with_capacity(payload.len() + 10);
+        write_length(&mut message, payload.len() as u64);
+        message.extend_from_slice(payload);
+        let bit_at = |i: usize| message.get(i / 8).is_some_and(|byte| byte >> (7 - i % 8) & 1 == 1);
+        let total_bits = message.len() * 8;
+
+        let mut words: Vec<&str> = Vec::new();
+        let mut interval = Interval::FULL;
+        let mut offset = 0;
+        let mut idle = 0;
+        let mut settled = Vec::new();
+        while offset < total_bits {
+            // The next PRECISION bits of the message, padded with zeros past its end.
+            let target = (0..PRECISION as usize).fold(0u64, |acc, i| acc << 1 | u64::from(bit_at(offset + i)));
+            let candidates = self.candidates(&words);
+            let (word, share) = interval
+                .split(&candidates)
+                .into_iter()
+                .find(|(_, share)| share.low <= target && target < share.high)
+                .expect("the shares cover the interval");
+            idle = if share == interval { idle + 1 } else { 0 };
+            if idle > MAX_IDLE_WORDS {
+                return Err(StegoError::NoEntropy);
+            }
+            interval = share;
+            settled.clear();
+            interval.settle(&mut settled);
+            offset += settled.len();
+            words.push(word);
+        }
+
+        let mut text = words.join(" ");
+        if let Some(first) = text.chars().next() {
+            text.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+        }
+        Ok(text)
+    }
+
+    /// Decodes a payload from text produced by [`StegoCodec::encode`] with the same model.
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, StegoError> {
+        let mut words: Vec<&str> = text.split_whitespace().collect();
+        // The first word was drawn lowercase and capitalized afterwards.
+        let first = words.first().map(|w| w.to_lowercase());
+        let mut bits = Vec::new();
+        let mut interval = Interval::FULL;
+        for index in 0..words.len() {
+            let word = if index == 0 { first.as_deref().unwrap_or_default() } else { words[index] };
+            let candidates = self.candidates(&words[..index]);
+            let (candidate, share) = interval
+                .split(&candidates)
+                .into_iter()
+                .find(|&(candidate, share)| candidate == word && share.low < share.high)
+                .ok_or_else(|| StegoError::UnknownWord { index, word: words[index].to_string() })?;
+            words[index] = candidate;
+            interval = share;
+            interval.settle(&mut bits);
+        }
+
+        let bytes: Vec<u8> = bits
+            .chunks_exact(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &bit| acc << 1 | u8::from(bit)))
+            .collect();
+        let (len, header) = read_length(&bytes).ok_or(StegoError::Truncated)?;
+        let end = usize::try_from(len).ok().and_then(|len| header.checked_add(len)).ok_or(StegoError::Truncated)?;
+        bytes.get(header..end).map(<[u8]>::to_vec).ok_or(StegoError::Truncated)
+    }
+}
+
+/// Appends `len` as a LEB128 varint.
+fn write_length(out: &mut Vec<u8>, mut len: u64) {
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Reads a LEB128 varint, returning it and the number of bytes it took.
+fn read_length(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut len = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(10) {
+        len |= u64::from(byte & 0x7f).checked_shl(7 * i as u32)?;
+        if byte & 0x80 == 0 {
+            return Some((len, i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{QuickCheck, TestResult};
+    use std::sync::OnceLock;
+
+    const SEED: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    #[test]
+    fn test_round_trip() {
+        let model = MarkovModel::train(SEED, 1);
+        let codec = StegoCodec::new(&model).unwrap();
+        for payload in [&b""[..], b"\0", b"payload-id:4711", &[0xff; 40], &(0..=255).collect::<Vec<u8>>()] {
+            let text = codec.encode(payload).unwrap();
+            assert!(text.chars().next().unwrap().is_uppercase(), "{}", text);
+            assert_eq!(codec.decode(&text).unwrap(), payload, "{}", text);
+        }
+        // The words come from the model, so the text passes for generated filler.
+        let text = codec.encode(b"test run 42").unwrap();
+        let counts = model.word_counts();
+        assert!(text.split_whitespace().all(|w| counts.contains_key(&w.to_lowercase())), "{}", text);
+    }
+
+    /// A codec over an order-2 model, shared by the property tests, which take plain functions.
+    fn codec() -> &'static StegoCodec<'static> {
+        static MODEL: OnceLock<MarkovModel> = OnceLock::new();
+        static CODEC: OnceLock<StegoCodec<'static>> = OnceLock::new();
+        CODEC.get_or_init(|| StegoCodec::new(MODEL.get_or_init(|| MarkovModel::train(SEED, 2))).unwrap())
+    }
+
+    #[test]
+    fn test_round_trip_property() {
+        fn round_trips(payload: Vec<u8>) -> bool {
+            codec().decode(&codec().encode(&payload).unwrap()) == Ok(payload)
+        }
+        // Decoding accepts any whitespace between the words.
+        fn survives_reflow(payload: Vec<u8>) -> bool {
+            let text = codec().encode(&payload).unwrap().replace(' ', "\n  ");
+            codec().decode(&text) == Ok(payload)
+        }
+        QuickCheck::new().tests(100).quickcheck(round_trips as fn(Vec<u8>) -> bool);
+        QuickCheck::new().tests(30).quickcheck(survives_reflow as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn test_truncated_and_foreign_text_is_rejected() {
+        fn cut_short(payload: Vec<u8>) -> TestResult {
+            if payload.len() < 4 {
+                return TestResult::discard();
+            }
+            let text = codec().encode(&payload).unwrap();
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let shorter = words[..words.len() / 2].join(" ");
+            TestResult::from_bool(codec().decode(&shorter) != Ok(payload))
+        }
+        QuickCheck::new().tests(50).quickcheck(cut_short as fn(Vec<u8>) -> TestResult);
+
+        assert!(matches!(
+            codec().decode("Dolor sit banana"),
+            Err(StegoError::UnknownWord { index: 2, .. })
+        ));
+        assert_eq!(codec().decode(""), Err(StegoError::Truncated));
+        assert_eq!(StegoCodec::new(&MarkovModel::train("", 1)).unwrap_err(), StegoError::EmptyModel);
+    }
+
+    #[test]
+    fn test_settle_shifts_out_shared_bits() {
+        let mut bits = Vec::new();
+        let mut interval = Interval { low: 0b1011 << (PRECISION - 4), high: 0b1100 << (PRECISION - 4) };
+        interval.settle(&mut bits);
+        assert_eq!(bits, [true, false, true, true]);
+        assert_eq!(interval, Interval::FULL);
+
+        let mut straddling = Interval { low: WHOLE / 2 - 1, high: WHOLE / 2 + 1 };
+        bits.clear();
+        straddling.settle(&mut bits);
+        assert!(bits.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+//! Sentence and paragraph structure for generated Lorem Ipsum–like text.
+//!
+//! [`MarkovModel::generate_with_rng`] produces a flat run of words carrying whatever punctuation
+//! happened to be attached in the seed. The functions here strip that punctuation and lay the
+//! words out as sentences (capitalized first word, terminal period, occasional commas) grouped
+//! into paragraphs, with lengths drawn from the ranges in a [`TextShape`].
+
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+use crate::model::{GenerateError, MarkovModel};
+
+/// The unit in which a caller asks for generated text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextUnit {
+    /// A flat run of words, exactly as the model produces them.
+    Words,
+    /// Punctuated sentences separated by single spaces.
+    Sentences,
+    /// Paragraphs of sentences separated by blank lines.
+    Paragraphs,
+}
+
+/// Controls the length of sentences and paragraphs and how often commas appear.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextShape {
+    /// How many words a sentence may have.
+    pub sentence_words: RangeInclusive<usize>,
+    /// How many sentences a paragraph may have.
+    pub paragraph_sentences: RangeInclusive<usize>,
+    /// The chance of a comma after any word that is neither among the first two
+    /// nor the last two words of its sentence.
+    pub comma_rate: f64,
+}
+
+impl Default for TextShape {
+    /// Sentences of 6–14 words in paragraphs of 3–6 sentences, with roughly one comma
+    /// every ten words, which is close to the classic Lorem Ipsum passage.
+    fn default() -> Self {
+        TextShape {
+            sentence_words: 6..=14,
+            paragraph_sentences: 3..=6,
+            comma_rate: 0.1,
+        }
+    }
+}
+
+/// Draws a length from `range`, treating empty or zero-based ranges as at least one.
+fn draw_len<R: Rng + ?Sized>(range: &RangeInclusive<usize>, rng: &mut R) -> usize {
+    let min = (*range.start()).max(1);
+    let max = (*range.end()).max(min);
+    rng.random_range(min..=max)
+}
+
+/// Strips surrounding punctuation from a generated word and lowercases it.
+fn bare_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Draws `num_words` bare words from the model, topping up when a generated token
+/// was nothing but punctuation. Topping up gives up after `num_words * 4` draws, so a model
+/// that mostly generates punctuation may return fewer words; one that generates no words at
+/// all is a [`GenerateError::NoWords`].
+pub(crate) fn draw_words<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    num_words: usize,
+    rng: &mut R,
+) -> Result<Vec<String>, GenerateError> {
+    let mut words = Vec::with_capacity(num_words);
+    // Give up on topping up if the model only ever produces punctuation.
+    for _ in 0..num_words.max(1) * 4 {
+        if words.len() >= num_words {
+            break;
+        }
+        let run = model.generate_with_rng(num_words - words.len(), rng)?;
+        words.extend(run.split_whitespace().map(bare_word).filter(|w| !w.is_empty()));
+    }
+    if words.is_empty() && num_words > 0 {
+        return Err(GenerateError::NoWords);
+    }
+    words.truncate(num_words);
+    Ok(words)
+}
+
+/// Generates one sentence: a capitalized first word, occasional commas and a terminal period.
+pub fn generate_sentence_with_rng<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    let num_words = draw_len(&shape.sentence_words, rng);
+    let words = draw_words(model, num_words, rng)?;
+    let mut sentence = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                sentence.extend(first.to_uppercase());
+                sentence.push_str(chars.as_str());
+            }
+        } else {
+            sentence.push(' ');
+            sentence.push_str(word);
+        }
+        if i >= 2 && i + 2 < words.len() && rng.random_bool(shape.comma_rate.clamp(0.0, 1.0)) {
+            sentence.push(',');
+        }
+    }
+    if !sentence.is_empty() {
+        sentence.push('.');
+    }
+    Ok(sentence)
+}
+
+/// Generates `count` sentences separated by single spaces.
+pub fn generate_sentences_with_rng<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    count: usize,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    let sentences = (0..count)
+        .map(|_| generate_sentence_with_rng(model, shape, rng))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sentences.join(" "))
+}
+
+/// Generates one paragraph with a number of sentences drawn from `shape.paragraph_sentences`.
+pub fn generate_paragraph_with_rng<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    let count = draw_len(&shape.paragraph_sentences, rng);
+    generate_sentences_with_rng(model, count, shape, rng)
+}
+
+/// Generates `count` paragraphs separated by blank lines.
+pub fn generate_paragraphs_with_rng<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    count: usize,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    let paragraphs = (0..count)
+        .map(|_| generate_paragraph_with_rng(model, shape, rng))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(paragraphs.join("\n\n"))
+}
+
+/// Generates `count` words, sentences or paragraphs of text.
+/// Returns [`GenerateError::EmptyModel`] if the model has nothing to generate from, and
+/// [`GenerateError::NoWords`] if sentences are asked for and it only generates punctuation.
+pub fn generate_text_with_rng<R: Rng + ?Sized>(
+    model: &MarkovModel,
+    unit: TextUnit,
+    count: usize,
+    shape: &TextShape,
+    rng: &mut R,
+) -> Result<String, GenerateError> {
+    match unit {
+        TextUnit::Words => model.generate_with_rng(count, rng),
+        TextUnit::Sentences => generate_sentences_with_rng(model, count, shape, rng),
+        TextUnit::Paragraphs => generate_paragraphs_with_rng(model, count, shape, rng),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lorem::seeded_rng;
+
+    const PASSAGE: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    #[test]
+    fn test_sentences_are_punctuated() {
+        let model = MarkovModel::train(PASSAGE, 1);
+        let shape = TextShape { sentence_words: 4..=9, ..TextShape::default() };
+        let mut rng = seeded_rng(5);
+        for _ in 0..50 {
+            let sentence = generate_sentence_with_rng(&model, &shape, &mut rng).unwrap();
+            assert!(sentence.chars().next().unwrap().is_uppercase(), "{}", sentence);
+            assert!(sentence.ends_with('.') && !sentence.ends_with(",."), "{}", sentence);
+            assert_eq!(sentence.matches('.').count(), 1, "{}", sentence);
+            let words = sentence.split_whitespace().count();
+            assert!((4..=9).contains(&words), "{} has {} words", sentence, words);
+        }
+    }
+
+    #[test]
+    fn test_comma_rate() {
+        let model = MarkovModel::train(PASSAGE, 1);
+        let mut rng = seeded_rng(6);
+        let none = TextShape { comma_rate: 0.0, ..TextShape::default() };
+        assert!(!generate_sentences_with_rng(&model, 20, &none, &mut rng).unwrap().contains(','));
+        let text = generate_sentences_with_rng(&model, 200, &TextShape::default(), &mut rng).unwrap();
+        let ratio = text.matches(',').count() as f64 / text.split_whitespace().count() as f64;
+        assert!((0.03..0.12).contains(&ratio), "comma ratio {}", ratio);
+
+        // Every word may take a comma except the first two and the last two.
+        let always = TextShape { comma_rate: 1.0, sentence_words: 6..=6, ..TextShape::default() };
+        let sentence = generate_sentence_with_rng(&model, &always, &mut rng).unwrap();
+        let commas: Vec<bool> = sentence.split_whitespace().map(|word| word.ends_with(',')).collect();
+        assert_eq!(commas, [false, false, true, true, false, false], "{}", sentence);
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let model = MarkovModel::train(PASSAGE, 2);
+        let shape = TextShape { paragraph_sentences: 2..=3, ..TextShape::default() };
+        let text = generate_text_with_rng(&model, TextUnit::Paragraphs, 4, &shape, &mut seeded_rng(7)).unwrap();
+        let paragraphs: Vec<&str> = text.split("\n\n").collect();
+        assert_eq!(paragraphs.len(), 4);
+        for paragraph in paragraphs {
+            assert!((2..=3).contains(&paragraph.matches('.').count()), "{}", paragraph);
+        }
+        assert_eq!(
+            text,
+            generate_text_with_rng(&model, TextUnit::Paragraphs, 4, &shape, &mut seeded_rng(7)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_empty_model() {
+        let model = MarkovModel::train("", 1);
+        for unit in [TextUnit::Words, TextUnit::Sentences, TextUnit::Paragraphs] {
+            let text = generate_text_with_rng(&model, unit, 3, &TextShape::default(), &mut seeded_rng(0));
+            assert_eq!(text, Err(GenerateError::EmptyModel));
+        }
+    }
+
+    #[test]
+    fn test_punctuation_only_model() {
+        let model = MarkovModel::train("-- ... -- ... !", 1);
+        let text = generate_text_with_rng(&model, TextUnit::Sentences, 2, &TextShape::default(), &mut seeded_rng(0));
+        assert_eq!(text, Err(GenerateError::NoWords));
+        assert_eq!(draw_words(&model, 0, &mut seeded_rng(0)), Ok(Vec::new()));
+
+        // Topping up gives up eventually, so a model of mostly punctuation draws fewer words.
+        let seed: Vec<String> = (1..=200).map(|n| if n % 20 == 0 { "lorem".to_string() } else { "-".repeat(n) }).collect();
+        let sparse = MarkovModel::train(&seed.join(" "), 1);
+        let words = draw_words(&sparse, 5, &mut seeded_rng(3)).unwrap();
+        assert!(!words.is_empty() && words.len() < 5 && words.iter().all(|w| w == "lorem"), "{:?}", words);
+    }
+}
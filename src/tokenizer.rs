@@ -0,0 +1,285 @@
+//! An offset-accurate tokenizer shared by the classifiers, filters and detectors.
+//!
+//! [`tokenize`] splits text into words, numbers, punctuation and whitespace, recording every
+//! token's byte and character range, so concatenating the tokens gives back the input exactly.
+//! It works on grapheme clusters, so an accent written as a combining mark stays with its
+//! letter. Apostrophes, hyphens and underscores between letters keep a word together
+//! ("don't", "well-known", "lorem_ipsum"); [`subwords`] then splits hyphenated, snake_case and
+//! camelCase words into the pieces a dictionary would know, and [`words`] does both.
+//! [`split_clitic`] finds the stem of an English contraction for dictionary lookups, and
+//! [`sentence_ids`] tells which sentence each word belongs to.
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a [`Token`] contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Letters, possibly with digits, joined apostrophes, hyphens or underscores.
+    Word,
+    /// Digits only.
+    Number,
+    /// A single grapheme that is neither alphanumeric nor whitespace, e.g. `,` or `🍅`.
+    Punctuation,
+    /// A run of whitespace.
+    Whitespace,
+}
+
+/// A slice of the tokenized text and where it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The token's text.
+    pub text: &'a str,
+    /// What the token contains.
+    pub kind: TokenKind,
+    /// Byte range of the token in the tokenized text.
+    pub bytes: Range<usize>,
+    /// Character (`char`) range of the token in the tokenized text.
+    pub chars: Range<usize>,
+}
+
+impl Token<'_> {
+    /// Whether the token is a word or a number.
+    pub fn is_word(&self) -> bool {
+        matches!(self.kind, TokenKind::Word | TokenKind::Number)
+    }
+}
+
+/// Characters that join two alphanumeric runs into one word.
+fn is_joiner(grapheme: &str) -> bool {
+    matches!(grapheme, "'" | "’" | "-" | "_")
+}
+
+/// Whether a grapheme cluster starts with a letter or digit.
+fn is_alphanumeric(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+fn is_whitespace(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Splits `text` into tokens. Concatenating the tokens' text gives back `text`.
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let graphemes: Vec<(usize, &str)> = text.grapheme_indices(true).collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut chars = 0;
+    while i < graphemes.len() {
+        let (start, first) = graphemes[i];
+        let mut end = i + 1;
+        let kind = if is_whitespace(first) {
+            while end < graphemes.len() && is_whitespace(graphemes[end].1) {
+                end += 1;
+            }
+            TokenKind::Whitespace
+        } else if is_alphanumeric(first) {
+            loop {
+                match graphemes.get(end) {
+                    Some((_, g)) if is_alphanumeric(g) => end += 1,
+                    Some((_, g)) if is_joiner(g) && graphemes.get(end + 1).is_some_and(|(_, n)| is_alphanumeric(n)) => {
+                        end += 2
+                    }
+                    _ => break,
+                }
+            }
+            TokenKind::Word
+        } else {
+            TokenKind::Punctuation
+        };
+        let byte_end = graphemes.get(end).map_or(text.len(), |&(offset, _)| offset);
+        let token_text = &text[start..byte_end];
+        let kind = if kind == TokenKind::Word && token_text.chars().all(|c| c.is_ascii_digit()) {
+            TokenKind::Number
+        } else {
+            kind
+        };
+        let char_len = token_text.chars().count();
+        tokens.push(Token { text: token_text, kind, bytes: start..byte_end, chars: chars..chars + char_len });
+        chars += char_len;
+        i = end;
+    }
+    tokens
+}
+
+/// Splits a word at hyphens, underscores and camelCase humps, keeping offsets into the
+/// original text: "parseHTTPRequest" becomes "parse", "HTTP", "Request" and "utf8Decode"
+/// becomes "utf8", "Decode". Apostrophes stay inside their word. Other tokens are returned
+/// unchanged.
+pub fn subwords<'a>(token: &Token<'a>) -> Vec<Token<'a>> {
+    if token.kind != TokenKind::Word {
+        return vec![token.clone()];
+    }
+    let chars: Vec<(usize, char)> = token.text.char_indices().collect();
+    let mut pieces = Vec::new();
+    let mut piece_start: Option<usize> = None;
+    let push = |from: usize, to: usize, pieces: &mut Vec<Token<'a>>| {
+        let text = &token.text[chars[from].0..chars.get(to).map_or(token.text.len(), |&(b, _)| b)];
+        let bytes = token.bytes.start + chars[from].0;
+        let kind = if text.chars().all(|c| c.is_ascii_digit()) { TokenKind::Number } else { TokenKind::Word };
+        pieces.push(Token {
+            text,
+            kind,
+            bytes: bytes..bytes + text.len(),
+            chars: token.chars.start + from..token.chars.start + to,
+        });
+    };
+    for (k, &(_, c)) in chars.iter().enumerate() {
+        if c == '-' || c == '_' {
+            if let Some(start) = piece_start.take() {
+                push(start, k, &mut pieces);
+            }
+            continue;
+        }
+        let hump = k > 0 && c.is_uppercase() && {
+            let prev = chars[k - 1].1;
+            let next_lower = chars.get(k + 1).is_some_and(|&(_, n)| n.is_lowercase());
+            prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower)
+        };
+        match piece_start {
+            Some(start) if hump => {
+                push(start, k, &mut pieces);
+                piece_start = Some(k);
+            }
+            None => piece_start = Some(k),
+            _ => {}
+        }
+    }
+    if let Some(start) = piece_start {
+        push(start, chars.len(), &mut pieces);
+    }
+    pieces
+}
+
+/// Clitics written after an apostrophe, as in "you're" or "it's". "n't" is handled apart,
+/// since it starts before the apostrophe.
+const CLITICS: &[&str] = &["d", "ll", "m", "re", "s", "ve"];
+
+/// Splits an English contraction into its stem and clitic: "don't" becomes "do" and "n't",
+/// "You're" becomes "You" and "'re". Both `'` and `’` are accepted as the apostrophe.
+/// Returns `None` if `word` does not end in a clitic, or is nothing but one.
+pub fn split_clitic(word: &str) -> Option<(&str, &str)> {
+    let apostrophe = word.rfind(['\'', '’'])?;
+    let after = &word[apostrophe..].trim_start_matches(['\'', '’']);
+    let start = if after.eq_ignore_ascii_case("t") && word[..apostrophe].ends_with(['n', 'N']) {
+        apostrophe - 1
+    } else if CLITICS.iter().any(|clitic| after.eq_ignore_ascii_case(clitic)) {
+        apostrophe
+    } else {
+        return None;
+    };
+    (start > 0).then(|| word.split_at(start))
+}
+
+/// The words and numbers of `text`, split into [`subwords`], without punctuation or whitespace.
+pub fn words(text: &str) -> impl Iterator<Item = Token<'_>> {
+    tokenize(text)
+        .into_iter()
+        .filter(Token::is_word)
+        .flat_map(|token| subwords(&token))
+}
+
+/// The sentence each of `words` belongs to, counted from 0. A sentence ends at a word
+/// followed by `.`, `!` or `?`, or by a blank line. Pass every word of `text`, as [`words`]
+/// gives them, and filter afterwards: a sentence ending after a dropped word, such as the
+/// number in "sit amet 2. Please", is only seen from that word.
+pub(crate) fn sentence_ids(text: &str, words: &[Token]) -> Vec<usize> {
+    let mut sentence = 0;
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let id = sentence;
+            // Words exclude punctuation, so look at what separates this word from the next.
+            let gap = &text[word.bytes.end..words.get(i + 1).map_or(text.len(), |next| next.bytes.start)];
+            let attached = gap.split(char::is_whitespace).next().unwrap_or_default();
+            if attached.contains(['.', '!', '?']) || gap.matches('\n').count() >= 2 {
+                sentence += 1;
+            }
+            id
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(tokens: impl IntoIterator<Item = Token<'a>>) -> Vec<&'a str> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    fn words_of(text: &str) -> Vec<Token<'_>> {
+        tokenize(text).into_iter().filter(Token::is_word).collect()
+    }
+
+    #[test]
+    fn test_tokenize_separates_punctuation() {
+        let text = "Lorem ipsum, dolor  sit amet.\n(Don't) well-known 42";
+        let tokens = tokenize(text);
+        assert_eq!(tokens.iter().map(|t| t.text).collect::<String>(), text);
+        let words: Vec<&str> = tokens.iter().filter(|t| t.is_word()).map(|t| t.text).collect();
+        assert_eq!(words, ["Lorem", "ipsum", "dolor", "sit", "amet", "Don't", "well-known", "42"]);
+        assert_eq!(tokens.last().unwrap().kind, TokenKind::Number);
+        let comma = tokens.iter().find(|t| t.text == ",").unwrap();
+        assert_eq!((comma.kind, comma.bytes.clone()), (TokenKind::Punctuation, 11..12));
+        assert!(tokens.iter().any(|t| t.text == "  " && t.kind == TokenKind::Whitespace));
+        // Joiners only count between letters or digits.
+        assert_eq!(texts(words_of("-lorem- 'ipsum' a--b")), ["lorem", "ipsum", "a", "b"]);
+    }
+
+    #[test]
+    fn test_offsets_with_non_ascii() {
+        let text = "Café\u{a0}naïve 🍅cafe\u{301}s — straße.";
+        for token in tokenize(text) {
+            assert_eq!(&text[token.bytes.clone()], token.text);
+            let by_chars: String = text.chars().skip(token.chars.start).take(token.chars.len()).collect();
+            assert_eq!(by_chars, token.text);
+        }
+        assert_eq!(texts(words(text)), ["Café", "naïve", "cafe\u{301}s", "straße"]);
+        let decomposed = words(text).nth(2).unwrap();
+        assert_eq!((decomposed.bytes, decomposed.chars), (18..25, 12..18));
+    }
+
+    #[test]
+    fn test_subwords() {
+        assert_eq!(texts(words("loremIpsum lorem_ipsum_dolor well-known")), ["lorem", "Ipsum", "lorem", "ipsum", "dolor", "well", "known"]);
+        assert_eq!(texts(words("parseHTTPRequest XMLHttpRequest utf8Decode")), ["parse", "HTTP", "Request", "XML", "Http", "Request", "utf8", "Decode"]);
+        assert_eq!(texts(words("don't O'Brien ABC")), ["don't", "O'Brien", "ABC"]);
+        let text = "x = fooBar_baz;";
+        for word in words(text) {
+            assert_eq!(&text[word.bytes.clone()], word.text);
+        }
+        let bar = words(text).nth(2).unwrap();
+        assert_eq!((bar.text, bar.bytes, bar.chars), ("Bar", 7..10, 7..10));
+        let number = words("v2_10").nth(1).unwrap();
+        assert_eq!((number.text, number.kind), ("10", TokenKind::Number));
+    }
+
+    #[test]
+    fn test_sentence_ids() {
+        let text = "Lorem ipsum dolor sit amet 2. Please read the well-known terms!\n\nAlso this\nline";
+        let words: Vec<Token> = words(text).collect();
+        let ids = sentence_ids(text, &words);
+        let sentences: Vec<(&str, usize)> = words.iter().map(|w| w.text).zip(ids).collect();
+        assert_eq!(sentences, [
+            ("Lorem", 0), ("ipsum", 0), ("dolor", 0), ("sit", 0), ("amet", 0), ("2", 0),
+            ("Please", 1), ("read", 1), ("the", 1), ("well", 1), ("known", 1), ("terms", 1),
+            ("Also", 2), ("this", 2), ("line", 2),
+        ]);
+        assert!(sentence_ids("", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_split_clitic() {
+        assert_eq!(split_clitic("don't"), Some(("do", "n't")));
+        assert_eq!(split_clitic("You're"), Some(("You", "'re")));
+        assert_eq!(split_clitic("it’s"), Some(("it", "’s")));
+        assert_eq!(split_clitic("CAN'T"), Some(("CA", "N'T")));
+        assert_eq!(split_clitic("we'll"), Some(("we", "'ll")));
+        for word in ["O'Brien", "rock'n'roll", "lorem", "n't", "'s"] {
+            assert_eq!(split_clitic(word), None, "{}", word);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Keyed, reversible filler watermarks.
+//!
+//! [`Watermark::embed`] tags text by inserting single filler words from a lorem model between
+//! some of its words. A secret key decides which gaps receive a word and which word goes
+//! there, so without the key the filler cannot be told apart from any other injected
+//! Lorem Ipsum. [`Watermark::extract`] recomputes the same choices, removes exactly the
+//! inserted words and checks them.
+//!
+//! Whether a gap receives a word depends only on the key, the word's position and the word
+//! before the gap, so extraction can find the gaps without knowing the clean text. The
+//! inserted words themselves are derived from a digest of the whole clean text, so editing,
+//! adding or removing any word after tagging makes verification fail.
+
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::model::MarkovModel;
+use crate::tokenizer::{self, Token, TokenKind};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Domain separators, so a gap decision can never collide with a word choice.
+const POSITION_LABEL: &[u8] = b"lorem-ipsumlike/watermark/position";
+const FILLER_LABEL: &[u8] = b"lorem-ipsumlike/watermark/filler";
+
+/// An error raised while embedding or extracting a watermark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatermarkError {
+    /// The model has no plain words to insert.
+    EmptyModel,
+    /// The key chose no gap in the text, so there is nothing to carry the watermark;
+    /// the text is too short for the configured density.
+    NoRoom,
+    /// None of the gaps the key chooses holds a word from the model: the text was not
+    /// tagged with this key and model.
+    NotFound,
+    /// Filler words were found, but they are not the ones the key expects: the filler or
+    /// the clean text was changed after tagging.
+    Tampered {
+        /// How many filler words the key expects.
+        expected: usize,
+        /// How many of them were found as expected.
+        matched: usize,
+    },
+}
+
+impl fmt::Display for WatermarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatermarkError::EmptyModel => write!(f, "the model has no words to insert"),
+            WatermarkError::NoRoom => write!(f, "the text has no gap chosen for the watermark"),
+            WatermarkError::NotFound => write!(f, "the text carries no watermark for this key"),
+            WatermarkError::Tampered { expected, matched } => write!(
+                f,
+                "the watermark does not verify ({} of {} filler words match)",
+                matched, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WatermarkError {}
+
+/// A keyed watermark over the vocabulary of a lorem model.
+#[derive(Clone)]
+pub struct Watermark {
+    key: Vec<u8>,
+    /// Plain lowercase words with their cumulative training counts, sorted by word.
+    vocabulary: Vec<(String, u64)>,
+    total: u64,
+    density: f64,
+}
+
+impl fmt::Debug for Watermark {
+    /// Leaves the key out, so it does not end up in logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watermark")
+            .field("vocabulary", &self.vocabulary.len())
+            .field("density", &self.density)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Watermark {
+    /// The share of gaps between words that receive a filler word by default.
+    pub const DEFAULT_DENSITY: f64 = 0.2;
+
+    /// Creates a watermark for `key` that inserts words from `model`, chosen in proportion
+    /// to how often they occur in its training text. Only words that the tokenizer reads
+    /// as one plain word are used. Returns [`WatermarkError::EmptyModel`] if there are none.
+    pub fn new(key: &[u8], model: &MarkovModel) -> Result<Self, WatermarkError> {
+        let mut words: Vec<(String, u32)> = model
+            .word_counts()
+            .into_iter()
+            .filter(|(word, _)| word.chars().count() >= 2 && word.chars().all(char::is_alphabetic))
+            .collect();
+        words.sort_unstable();
+        let mut total = 0;
+        let vocabulary: Vec<(String, u64)> = words
+            .into_iter()
+            .map(|(word, count)| {
+                total += u64::from(count);
+                (word, total)
+            })
+            .collect();
+        if vocabulary.is_empty() {
+            return Err(WatermarkError::EmptyModel);
+        }
+        Ok(Watermark { key: key.to_vec(), vocabulary, total, density: Self::DEFAULT_DENSITY })
+    }
+
+    /// Sets the share of gaps that receive a filler word, clamped to `[0, 1]`.
+    /// Extraction must use the same density as embedding.
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = if density.is_nan() { 0.0 } else { density.clamp(0.0, 1.0) };
+        self
+    }
+
+    /// The share of gaps that receive a filler word.
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    /// Inserts the keyed filler words into `text`. Everything else in the text is kept
+    /// byte for byte. Returns [`WatermarkError::NoRoom`] if the key chooses no gap.
+    pub fn embed(&self, text: &str) -> Result<String, WatermarkError> {
+        let tokens = tokenizer::tokenize(text);
+        let digest = Sha256::digest(text.as_bytes());
+        let mut output = String::with_capacity(text.len());
+        let mut position = 0;
+        let mut inserted = 0;
+        for at in self.chosen_gaps(&tokens) {
+            let token = &tokens[at];
+            output.push_str(&text[position..token.bytes.end]);
+            output.push(' ');
+            output.push_str(self.filler(&digest, inserted));
+            position = token.bytes.end;
+            inserted += 1;
+        }
+        if inserted == 0 {
+            return Err(WatermarkError::NoRoom);
+        }
+        output.push_str(&text[position..]);
+        Ok(output)
+    }
+
+    /// Removes the keyed filler words from `text` and verifies them, returning the clean text.
+    ///
+    /// Returns [`WatermarkError::NotFound`] if the text was not tagged with this key, and
+    /// [`WatermarkError::Tampered`] if the filler or the clean text was changed after tagging.
+    pub fn extract(&self, text: &str) -> Result<String, WatermarkError> {
+        let tokens = tokenizer::tokenize(text);
+        let mut clean = String::with_capacity(text.len());
+        let mut removed: Vec<&str> = Vec::new();
+        let mut position = 0;
+        let mut index = 0;
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            i += 1;
+            if !token.is_word() {
+                continue;
+            }
+            let original = index;
+            index += 1;
+            if !self.chooses(original, token.text) {
+                continue;
+            }
+            // An inserted word sits between its word and the original gap: "word filler<gap>".
+            let inserted = match &tokens[i..] {
+                [space, filler, after, ..]
+                    if space.text == " " && filler.kind == TokenKind::Word && after.kind == TokenKind::Whitespace =>
+                {
+                    filler
+                }
+                _ => continue,
+            };
+            clean.push_str(&text[position..token.bytes.end]);
+            position = inserted.bytes.end;
+            removed.push(inserted.text);
+            i += 2;
+        }
+        clean.push_str(&text[position..]);
+
+        let digest = Sha256::digest(clean.as_bytes());
+        let expected = self.chosen_gaps(&tokenizer::tokenize(&clean)).len();
+        let matched = removed
+            .iter()
+            .enumerate()
+            .filter(|&(n, word)| *word == self.filler(&digest, n))
+            .count();
+        // Words from the model where the key expects filler mean the text was tagged; their
+        // absence means it never was.
+        if !removed.iter().any(|word| self.in_vocabulary(word)) {
+            return Err(WatermarkError::NotFound);
+        }
+        if matched != expected || removed.len() != expected {
+            return Err(WatermarkError::Tampered { expected, matched });
+        }
+        Ok(clean)
+    }
+
+    /// Whether `text` carries an intact watermark for this key.
+    pub fn verify(&self, text: &str) -> bool {
+        self.extract(text).is_ok()
+    }
+
+    /// The indexes of the word tokens the key puts a filler word after: those it chooses
+    /// among the words followed by whitespace.
+    fn chosen_gaps(&self, tokens: &[Token<'_>]) -> Vec<usize> {
+        let mut index = 0;
+        let mut chosen = Vec::new();
+        for (at, token) in tokens.iter().enumerate() {
+            if !token.is_word() {
+                continue;
+            }
+            let gap = tokens.get(at + 1).is_some_and(|t| t.kind == TokenKind::Whitespace);
+            if gap && self.chooses(index, token.text) {
+                chosen.push(at);
+            }
+            index += 1;
+        }
+        chosen
+    }
+
+    /// Whether the key puts a filler word after the `index`th word, `word`.
+    fn chooses(&self, index: usize, word: &str) -> bool {
+        let value = self.prf(&[POSITION_LABEL, &(index as u64).to_le_bytes(), word.as_bytes()]);
+        // The top 53 bits give a uniform float in [0, 1).
+        ((value >> 11) as f64 / (1u64 << 53) as f64) < self.density
+    }
+
+    fn in_vocabulary(&self, word: &str) -> bool {
+        self.vocabulary.binary_search_by(|(known, _)| known.as_str().cmp(word)).is_ok()
+    }
+
+    /// The `n`th filler word for the clean text with the given digest.
+    fn filler(&self, digest: &[u8], n: usize) -> &str {
+        let pick = self.prf(&[FILLER_LABEL, &(n as u64).to_le_bytes(), digest]) % self.total;
+        let at = self.vocabulary.partition_point(|&(_, cumulative)| cumulative <= pick);
+        &self.vocabulary[at].0
+    }
+
+    /// The first eight bytes of the keyed MAC over `parts`.
+    fn prf(&self, parts: &[&[u8]]) -> u64 {
+        keyed_u64(&self.key, parts)
+    }
+}
+
+/// HMAC-SHA256 of `parts` under `key`. Each part is prefixed with its length, so no two
+/// different lists of parts are hashed alike.
+pub(crate) fn keyed_hash(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(&(part.len() as u64).to_le_bytes());
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+/// The first eight bytes of [`keyed_hash`] as a number.
+pub(crate) fn keyed_u64(key: &[u8], parts: &[&[u8]]) -> u64 {
+    let bytes = keyed_hash(key, parts);
+    u64::from_le_bytes(bytes[..8].try_into().expect("SHA-256 output is 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+    const TEXT: &str = "Our pipeline copies every release note into the changelog.\nReviewers then check the wording, fix typos and approve the final text before it ships.\n";
+
+    fn watermark(key: &[u8]) -> Watermark {
+        Watermark::new(key, &MarkovModel::train(SEED, 1)).unwrap().with_density(0.3)
+    }
+
+    #[test]
+    fn test_embed_and_extract() {
+        let mark = watermark(b"secret");
+        let tagged = mark.embed(TEXT).unwrap();
+        assert_ne!(tagged, TEXT);
+        assert_eq!(tagged, mark.embed(TEXT).unwrap());
+        assert!(tagged.split_whitespace().count() > TEXT.split_whitespace().count());
+        assert_eq!(mark.extract(&tagged), Ok(TEXT.to_string()));
+        assert!(mark.verify(&tagged));
+        // Plain text, or text tagged under another key, carries no watermark for this key.
+        assert_eq!(mark.extract(TEXT), Err(WatermarkError::NotFound));
+        let other = watermark(b"other").embed(TEXT).unwrap();
+        assert_ne!(other, tagged);
+        assert!(!mark.verify(&other));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let mark = watermark(b"secret");
+        let tagged = mark.embed(TEXT).unwrap();
+        let edited = tagged.replace("typos", "errors");
+        assert!(matches!(mark.extract(&edited), Err(WatermarkError::Tampered { .. })), "{}", edited);
+        let appended = format!("{}Also, the release is late.\n", tagged);
+        assert!(!mark.verify(&appended));
+
+        // Removing one filler word leaves the others in place but fails verification.
+        let clean_words: Vec<&str> = TEXT.split_whitespace().collect();
+        let first_filler = tagged.split_whitespace().zip(&clean_words).position(|(a, b)| a != *b).unwrap();
+        let mut words: Vec<&str> = tagged.split_whitespace().collect();
+        words.remove(first_filler);
+        let stripped = words.join(" ");
+        assert!(!mark.verify(&stripped));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Watermark::new(b"k", &MarkovModel::train("", 1)).unwrap_err(), WatermarkError::EmptyModel);
+        assert_eq!(watermark(b"k").with_density(0.0).embed(TEXT), Err(WatermarkError::NoRoom));
+        assert_eq!(watermark(b"k").embed("Hello."), Err(WatermarkError::NoRoom));
+        assert!(!format!("{:?}", watermark(b"secret")).contains("secret"));
+    }
+}